@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS breaks (
+    id INTEGER PRIMARY KEY,
+    session_id INTEGER NOT NULL REFERENCES work_sessions(id) ON DELETE CASCADE,
+    pause_start DATETIME NOT NULL,
+    pause_end DATETIME DEFAULT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE work_sessions ADD COLUMN note TEXT DEFAULT NULL;
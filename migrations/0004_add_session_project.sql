@@ -0,0 +1 @@
+ALTER TABLE work_sessions ADD COLUMN project TEXT DEFAULT NULL;
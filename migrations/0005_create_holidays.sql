@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS holidays (
+    date DATE PRIMARY KEY,
+    kind TEXT NOT NULL
+);
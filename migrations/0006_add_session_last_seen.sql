@@ -0,0 +1 @@
+ALTER TABLE work_sessions ADD COLUMN last_seen DATETIME DEFAULT NULL;
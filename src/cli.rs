@@ -1,50 +1,282 @@
 use crate::{
-    DB_FILE_PATH,
-    db::{WorktimeDatabase, WorktimeSession},
-    err::{CommandError, CommandResult},
+    MIGRATOR,
+    config::CONFIG,
+    db::{HolidayKind, WorktimeBreak, WorktimeDatabase, WorktimeSession},
+    err::{CommandError, CommandOutput, CommandResult},
+    format::{self, OutputFormat, ReportData},
+    stdout::{ResultFormat, TableRow, Tone, render_table},
     time::*,
 };
-use chrono::NaiveTime;
-use clap::{Parser, Subcommand};
-use std::{ops::Deref, process::Command};
+use chrono::{
+    DateTime, Datelike, Days, IsoWeek, Locale, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeDelta, Weekday,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand, error::ErrorKind};
+use clap_complete::Shell;
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    slice,
+};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
 #[derive(Parser)]
 #[command(name = "worktime", version)]
 pub struct Cli {
+    /// Print each result as text, as one JSON object or as the bare figure (e.g. `6.00`)
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub format: ResultFormat,
+    /// Don't color tables (also off when piped or `NO_COLOR` is set)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+    /// Database to use instead of `WORKTIME_DB` or the default location
+    #[arg(long, global = true, value_name = "FILE")]
+    pub db: Option<PathBuf>,
+    /// Preview what `correct`, `delete` or `import` would change without writing it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    /// Log debug details (command, date range, aggregated sessions, SQL) to stderr
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+    /// Only print errors (an explicit `--format json|plain` still prints results)
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+    /// Append all output (errors included) to this file instead of the terminal
+    #[arg(long, global = true, value_name = "FILE")]
+    pub output_file: Option<PathBuf>,
     #[command(subcommand)]
     pub command: WorktimeCommand,
 }
 
+impl Cli {
+    /// rejects report flags doubling a global one (`--output-format` with `--format`,
+    /// `--output` with `--output-file`) - clap only sees a global flag given after the
+    /// subcommand, so this checks either order
+    pub fn validated(self) -> Result<Self, clap::Error> {
+        let WorktimeCommand::Report { options, .. } = &self.command else {
+            return Ok(self);
+        };
+        let conflict = match (self.format, options.output_format) {
+            (ResultFormat::Text, _) | (_, OutputFormat::Table) => None,
+            _ => Some(("--output-format", "--format")),
+        }
+        .or_else(|| {
+            (options.output.is_some() && self.output_file.is_some())
+                .then_some(("--output", "--output-file"))
+        });
+        match conflict {
+            Some((report, global)) => Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                format!("the argument '{report}' cannot be used with '{global}'"),
+            )),
+            None => Ok(self),
+        }
+    }
+}
+
 /// responsible for stdin/stdout & logic
-#[derive(Debug, Subcommand, Clone, Copy)]
+#[derive(Debug, Subcommand, Clone, strum::IntoStaticStr)]
+#[allow(clippy::large_enum_variant)] // parsed once per invocation
 pub enum WorktimeCommand {
     /// Prints current state
-    Status,
+    Status {
+        /// Print as JSON, e.g. `{"running":true,"since":"09:12","elapsed_minutes":182}`
+        #[arg(long)]
+        json: bool,
+        /// Redraw the status every second until Ctrl-C
+        #[arg(long, conflicts_with = "json")]
+        watch: bool,
+        /// Also show the elapsed time and today's total
+        #[arg(short, long, conflicts_with = "json")]
+        detailed: bool,
+    },
     /// Start tracking time
-    Start,
+    #[command(alias = "open")]
+    Start {
+        /// What you are working on
+        #[arg(long)]
+        note: Option<String>,
+        /// Client/project the time is booked on
+        #[arg(long)]
+        project: Option<String>,
+    },
     /// Stop tracking time
-    Stop,
+    #[command(alias = "close")]
+    Stop {
+        /// Warn if the stopped session ran longer than this many minutes
+        #[arg(long, value_name = "MINUTES")]
+        threshold_alert: Option<u32>,
+        /// End the session where it was last seen, cutting an idle gap (see
+        /// WORKTIME_IDLE_AFTER_MINUTES)
+        #[arg(long)]
+        trim_idle: bool,
+    },
+    /// Pause the running session (e.g. for lunch)
+    Pause,
+    /// Resume the paused session
+    Resume,
     /// Report today's total work time
     Report {
         /// The kind of report to generate
         #[arg(value_enum, default_value_t = ReportKind::Day)]
         kind: ReportKind,
+        #[command(flatten)]
+        options: ReportOptions,
     },
     /// Correct QoL - sets start/end of session with id to hours:minutes
+    #[command(allow_negative_numbers = true)]
     Correct {
         /// n-th last session (0-based)
-        #[arg(default_value_t = 0)]
+        #[arg()]
         nth_last: u32,
-        #[arg(value_enum, default_value_t = CorrectionKind::Start)]
+        #[arg(value_enum)]
         kind: CorrectionKind,
         #[arg()]
+        hours: i16,
+        #[arg()]
+        minutes: i16,
+        /// Shift the current time by hours:minutes (signed) instead of setting it
+        #[arg(long)]
+        relative: bool,
+        /// Move the session (with its breaks) to this day first, e.g. `--date 2025-07-01`
+        #[arg(long, value_parser = date_spec)]
+        date: Option<NaiveDate>,
+    },
+    /// Record a finished session after the fact, e.g. `add 2025-07-01 09:00 17:30`
+    Add {
+        #[arg(value_parser = date_spec)]
+        date: NaiveDate,
+        /// HH:MM
+        #[arg()]
+        start: NaiveTime,
+        /// HH:MM
+        #[arg()]
+        end: NaiveTime,
+    },
+    /// Print the cumulative flex-time balance (worked − daily target per working day)
+    Balance {
+        /// First day to count (YYYY-MM-DD), defaults to `WORKTIME_FLEX_START` or the first session
+        #[arg(long, value_parser = date_spec)]
+        since: Option<NaiveDate>,
+    },
+    /// Tick the running session's elapsed time in place until stopped or Ctrl-C
+    Watch,
+    /// Mark days without a target (public holidays, vacation, sick leave)
+    Holiday {
+        #[command(subcommand)]
+        action: HolidayAction,
+    },
+    /// Reopen the last session if it was stopped only moments ago (`WORKTIME_CONTINUE_GRACE_MINUTES`)
+    Continue,
+    /// Discard the running session without recording it
+    Cancel {
+        /// Skip the confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Undo the last Start (removes the session) or Stop (reopens the session)
+    Undo,
+    /// Label the n-th last session (0-based) with what you worked on
+    Note {
+        #[arg()]
+        nth_last: u32,
+        #[arg()]
+        text: String,
+    },
+    /// List the recorded sessions, newest first
+    List {
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+        #[arg(long, default_value_t = 0)]
+        offset: u32,
+        /// Show all times moved by this many (signed) minutes, e.g. `--tz-shift=-60`
+        #[arg(long, value_name = "MINUTES", allow_hyphen_values = true)]
+        tz_shift: Option<i32>,
+    },
+    /// Export the sessions as CSV (id,date,start,end,duration_minutes)
+    Export {
+        /// File to write
+        path: PathBuf,
+        /// First day to export (YYYY-MM-DD)
+        #[arg(long, value_parser = date_spec)]
+        from: Option<NaiveDate>,
+        /// Last day to export (YYYY-MM-DD), defaults to today
+        #[arg(long, value_parser = date_spec)]
+        to: Option<NaiveDate>,
+    },
+    /// Import sessions from a CSV in the format of `export` (the id column is ignored)
+    Import {
+        /// File to read
+        path: PathBuf,
+        /// Skip sessions overlapping recorded ones instead of importing nothing
+        #[arg(long)]
+        skip_overlaps: bool,
+    },
+    /// Write a consistent snapshot of the database to a file
+    Backup {
+        /// File to write
+        path: PathBuf,
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Split the n-th last session (0-based) at HH:MM into two, e.g. for an untracked break
+    Split {
+        /// n-th last session (0-based)
+        #[arg()]
+        nth_last: u32,
+        #[arg()]
         hours: u8,
         #[arg()]
         minutes: u8,
     },
+    /// Delete the n-th last session (0-based)
+    Delete {
+        #[arg()]
+        nth_last: u32,
+        /// Skip the confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Merge all sessions of a day (or two back-to-back sessions) into one spanning session
+    Merge {
+        /// Day to merge (YYYY-MM-DD)
+        #[arg(
+            long,
+            required_unless_present = "first_nth",
+            conflicts_with = "first_nth",
+            value_parser = date_spec,
+        )]
+        date: Option<NaiveDate>,
+        /// n-th last session (0-based) to merge with `second_nth` instead of a whole day
+        #[arg(requires = "second_nth")]
+        first_nth: Option<u32>,
+        /// n-th last session (0-based) to merge with `first_nth`
+        #[arg()]
+        second_nth: Option<u32>,
+        /// End the merged session after the day's worked time instead of at the last end
+        /// (i.e. the gaps in between are subtracted as a break)
+        #[arg(long)]
+        preserve_total: bool,
+    },
     /// Sqlite3
     Sql,
+    /// Diagnose the setup (database, migrations, sqlite3, data)
+    Doctor,
+    /// Close all but the latest running session (at their start) after corruption
+    Repair {
+        /// Skip the confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Print shell completions (e.g. `worktime completions zsh > _worktime`)
+    #[command(hide = true)]
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
     /// Prints Clap's help
     /// NOTE: can't be named help
     /// (causes runtime panic due to conflict with clap's help)
@@ -68,12 +300,34 @@ pub enum MainMenuCommand {
     Start,
     /// Stop tracking time
     Stop,
+    /// Discard the running session
+    Cancel,
+    /// Undo the last Start or Stop
+    Undo,
+    /// Pause the running session
+    Pause,
+    /// Resume the paused session
+    Resume,
+    /// Reopen the session stopped moments ago
+    Continue,
     /// Report today's total work time
     Report,
+    /// Cumulative flex-time balance
+    Balance,
+    /// List the recorded sessions
+    List,
+    /// Record a forgotten session
+    Add,
     /// Correct QoL
     Correct,
+    /// Delete an erroneous session
+    Delete,
     /// Sqlite3
     Sql,
+    /// Diagnose the setup
+    Doctor,
+    /// Fix multiple running sessions
+    Repair,
     /// Print Clap's help
     Help,
     /// Exit program
@@ -92,6 +346,163 @@ pub enum ReportKind {
     Day,
     Week,
     Month,
+    Year,
+}
+
+/// what `--split-by` breaks the period total into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportSplit {
+    /// sub-total per project (sessions without one under `-`), same as `--by-project`
+    Project,
+}
+
+/// optional extras of [`WorktimeCommand::Report`]
+#[derive(Default, Debug, Args, Clone)]
+pub struct ReportOptions {
+    /// Read newline separated dates (YYYY-MM-DD) from stdin and report each day's total
+    #[arg(long)]
+    pub from_stdin: bool,
+    /// Report everything since the first tracked day (ignores the kind)
+    #[arg(long)]
+    pub since_first: bool,
+    /// Report the period containing this date (YYYY-MM-DD) instead of today's
+    #[arg(long, conflicts_with = "since_first", value_parser = date_spec)]
+    pub anchor: Option<NaiveDate>,
+    /// Report from this date (YYYY-MM-DD) on instead of the kind's period (ignores the kind)
+    #[arg(long, conflicts_with_all = ["since_first", "anchor"], value_parser = date_spec)]
+    pub since: Option<NaiveDate>,
+    /// Report up to this date (YYYY-MM-DD) instead of the kind's period (ignores the kind)
+    #[arg(long, conflicts_with_all = ["since_first", "anchor"], value_parser = date_spec)]
+    pub until: Option<NaiveDate>,
+    /// Leave out sessions shorter than this many minutes
+    #[arg(long, value_name = "MINUTES")]
+    pub min_session: Option<u32>,
+    /// Let a running session contribute nothing (stable output regardless of when run)
+    #[arg(long)]
+    pub normalize_open: bool,
+    /// Leave this session out of the report (repeatable)
+    #[arg(long = "exclude-session", value_name = "ID")]
+    pub exclude_sessions: Vec<i64>,
+    /// Round each day's total up to this many minutes before summing (billing)
+    #[arg(long, value_name = "MINUTES")]
+    pub round_each_day: Option<u32>,
+    /// Show the total as a percentage of a workweek of this many hours
+    #[arg(long, value_name = "HOURS")]
+    pub workweek_hours: Option<f64>,
+    /// Only include sessions starting on these weekdays (e.g. `mon,tue,wed,thu,fri`)
+    #[arg(long, value_delimiter = ',', value_name = "WEEKDAYS")]
+    pub weekday_mask: Vec<Weekday>,
+    /// Only include sessions booked on this project
+    #[arg(long, value_name = "NAME")]
+    pub project: Option<String>,
+    /// Print the total per project instead, e.g. `acme: 12.50h / beta: 3.00h`
+    #[arg(long)]
+    pub by_project: bool,
+    /// Print sub-totals per group instead of the total (`project` = `--by-project`)
+    #[arg(long, value_enum, value_name = "GROUP")]
+    pub split_by: Option<ReportSplit>,
+    /// Add each project's share of the period total, e.g. `acme: 6.00h (60%)`
+    #[arg(long)]
+    pub with_percent: bool,
+    /// Print the average per working day (Mon-Fri without holidays) of the period
+    #[arg(long)]
+    pub working_days_only: bool,
+    /// Flag sessions of the period that ran longer than this many minutes
+    #[arg(long, value_name = "MINUTES")]
+    pub threshold_alert: Option<u32>,
+    /// Flag idle gaps within a day that are longer than this many minutes
+    #[arg(long, value_name = "MINUTES")]
+    pub max_gap_alert: Option<u32>,
+    /// Project on which day this goal (in hours) will be met at the period's daily pace
+    #[arg(long, value_name = "HOURS")]
+    pub goal_eta: Option<f64>,
+    /// Print a histogram of the worked minutes per hour of day
+    #[arg(long)]
+    pub histogram_hours: bool,
+    /// Print tracked/target/balance per ISO week for this weekly target (in hours)
+    #[arg(long, value_name = "HOURS")]
+    pub weekly_target_grid: Option<f64>,
+    /// Print each ISO week's balance against this weekly target (in hours) as a trend
+    #[arg(long, value_name = "HOURS")]
+    pub compare_target_trend: Option<f64>,
+    /// Print the longest, shortest & average session length
+    #[arg(long)]
+    pub stats: bool,
+    /// Print the period's daily totals as a sparkline
+    #[arg(long)]
+    pub sparkline: bool,
+    /// Break the period down into a `date | start | end | duration` table
+    #[arg(long)]
+    pub table: bool,
+    /// List the period's sessions, marking the running one with its live elapsed time
+    #[arg(long)]
+    pub include_running_marker: bool,
+    /// Expect the hours of `WORKTIME_SCHEDULE` per weekday instead of the daily target
+    #[arg(long)]
+    pub target_from_schedule: bool,
+    /// Print just the signed balance against the daily target (e.g. `+1.50h`)
+    #[arg(long)]
+    pub balance_only: bool,
+    /// Print `iso_week,year,hours` CSV rows for the weeks of the period instead
+    #[arg(long)]
+    pub weekly_rollup_csv: bool,
+    /// Print just the summed overtime of the weeks above this weekly target, ignoring weeks below
+    #[arg(long, value_name = "HOURS")]
+    pub weekly_overtime_only: Option<f64>,
+    /// Name the days of the weekday breakdown in this locale, e.g. `de_DE` (default English)
+    #[arg(long, value_parser = locale)]
+    pub locale: Option<Locale>,
+    /// Tag days off in the weekday breakdown with `(holiday)`
+    #[arg(long)]
+    pub annotate_holidays: bool,
+    /// Show all times of `--table`/`--include-running-marker` moved by this many (signed)
+    /// minutes, e.g. `--tz-shift=-60`; the stored sessions stay as they are
+    #[arg(long, value_name = "MINUTES", allow_hyphen_values = true)]
+    pub tz_shift: Option<i32>,
+    /// Break each day down into gross time, breaks and net time
+    #[arg(long)]
+    pub net_of_breaks_detail: bool,
+    /// List the notes of the period's sessions below the total
+    #[arg(long)]
+    pub include_note_summary: bool,
+    /// Print nothing at all (instead of 0.00h) if nothing was tracked
+    #[arg(long)]
+    pub omit_empty_total_zero: bool,
+    /// Use a decimal comma for the hour figure (e.g. `6,00h`)
+    #[arg(long)]
+    pub decimal_comma: bool,
+    /// Output format of the report (renders the text result, so not with `--format`)
+    #[arg(long, value_enum, default_value_t)]
+    pub output_format: OutputFormat,
+    /// Write the report to this file instead of printing it (not with `--output-file`)
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Append to `--output` (with a timestamp header) instead of overwriting it
+    #[arg(long, requires = "output")]
+    pub append: bool,
+}
+
+impl ReportOptions {
+    /// `--since`/`--until` replace the kind's period
+    fn is_range(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum HolidayAction {
+    /// Mark a day as off (replaces an existing mark)
+    Add {
+        #[arg(value_parser = date_spec)]
+        date: NaiveDate,
+        #[arg(value_enum)]
+        kind: HolidayKind,
+    },
+    /// Unmark a day
+    Remove {
+        #[arg(value_parser = date_spec)]
+        date: NaiveDate,
+    },
 }
 
 #[derive(Default, Debug, Clone, Copy, clap::ValueEnum, EnumIter, Display)]
@@ -101,6 +512,16 @@ pub enum CorrectionKind {
     End,
 }
 
+/// the change `correct` makes to a session (see [`WorktimeCommand::Correct`])
+#[derive(Debug, Clone, Copy)]
+struct Correction {
+    kind: CorrectionKind,
+    hours: i16,
+    minutes: i16,
+    relative: bool,
+    date: Option<NaiveDate>,
+}
+
 impl ReportKind {
     pub fn wrapped_iter() -> ReportKindIter {
         ReportKind::iter()
@@ -108,82 +529,633 @@ impl ReportKind {
 }
 
 impl WorktimeCommand {
-    pub async fn execute(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+    /// the question to confirm before a destructive command runs, showing what it discards;
+    /// `None` if it needs none (e.g. `--yes`)
+    pub async fn confirmation(&self, db: &WorktimeDatabase) -> Option<String> {
         match self {
-            WorktimeCommand::Status => self.status(db).await,
-            WorktimeCommand::Start => self.start(db, clock).await,
-            WorktimeCommand::Stop => self.stop(db, clock).await,
-            WorktimeCommand::Report { kind } => self.report(db, *kind, clock).await,
+            WorktimeCommand::Delete {
+                nth_last,
+                yes: false,
+            } => db
+                .get_nth_last_session(*nth_last)
+                .await
+                .ok()
+                .map(|session| format!("Delete session '{session}'?")),
+            WorktimeCommand::Cancel { yes: false } => db
+                .get_last_session()
+                .await
+                .ok()
+                .flatten()
+                .filter(|last| last.is_open())
+                .map(|open| format!("Discard session started at {}?", display_time(&open.start))),
+            WorktimeCommand::Repair { yes: false } => {
+                Some("Close all but the latest running session at their start?".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// on a plain `stop` after an idle gap, whether to end the session where it was last seen
+    /// instead (answering yes turns on `--trim-idle`)
+    pub async fn idle_trim_prompt(
+        &self,
+        db: &WorktimeDatabase,
+        clock: &impl Clock,
+    ) -> Option<String> {
+        let WorktimeCommand::Stop {
+            trim_idle: false, ..
+        } = self
+        else {
+            return None;
+        };
+        let open = db.get_last_session().await.ok().flatten()?;
+        let last_seen = idle_since(db, &open, clock.get_now())
+            .await
+            .ok()
+            .flatten()?;
+        Some(format!(
+            "Idle since {} - end the session there?",
+            display_time(&last_seen)
+        ))
+    }
+
+    /// whether it writes to the database (`export`/`backup` only write files)
+    pub fn writes_db(&self) -> bool {
+        matches!(
+            self,
+            WorktimeCommand::Start { .. }
+                | WorktimeCommand::Stop { .. }
+                | WorktimeCommand::Pause
+                | WorktimeCommand::Resume
+                | WorktimeCommand::Correct { .. }
+                | WorktimeCommand::Add { .. }
+                | WorktimeCommand::Holiday { .. }
+                | WorktimeCommand::Continue
+                | WorktimeCommand::Cancel { .. }
+                | WorktimeCommand::Undo
+                | WorktimeCommand::Note { .. }
+                | WorktimeCommand::Import { .. }
+                | WorktimeCommand::Delete { .. }
+                | WorktimeCommand::Merge { .. }
+                | WorktimeCommand::Split { .. }
+                | WorktimeCommand::Repair { .. }
+        )
+    }
+
+    /// with `dry_run` the writing commands only describe their effect (or refuse if they can't)
+    pub async fn execute(
+        &self,
+        db: &WorktimeDatabase,
+        clock: &impl Clock,
+        dry_run: bool,
+    ) -> CommandResult<CommandOutput> {
+        let previewable = matches!(
+            self,
+            WorktimeCommand::Correct { .. }
+                | WorktimeCommand::Delete { .. }
+                | WorktimeCommand::Import { .. }
+        );
+        if dry_run && self.writes_db() && !previewable {
+            let name: &'static str = self.into();
+            return Err(format!("{name} doesn't support --dry-run").into());
+        }
+        let message = match self {
+            WorktimeCommand::Status { json: true, .. } => self.status_json(db, clock).await,
+            WorktimeCommand::Status { watch: true, .. } => self.watch_status(db, clock).await,
+            WorktimeCommand::Status { detailed: true, .. } => self.status_detailed(db, clock).await,
+            WorktimeCommand::Status { .. } => self.status(db, clock).await,
+            WorktimeCommand::Start { note, project } => {
+                return self
+                    .start(db, (note.as_deref(), project.as_deref()), clock)
+                    .await;
+            }
+            WorktimeCommand::Report { kind, options } => {
+                return self.report(db, *kind, options, clock).await;
+            }
+            WorktimeCommand::Stop {
+                threshold_alert,
+                trim_idle,
+            } => self.stop(db, *threshold_alert, *trim_idle, clock).await,
+            WorktimeCommand::Pause => self.pause(db, clock).await,
+            WorktimeCommand::Resume => self.resume(db, clock).await,
             WorktimeCommand::Correct {
                 nth_last,
                 kind,
                 hours,
                 minutes,
-            } => self.correct(db, *nth_last, *kind, *hours, *minutes).await,
-            WorktimeCommand::Sql => self.sqlite(),
+                relative,
+                date,
+            } => {
+                let correction = Correction {
+                    kind: *kind,
+                    hours: *hours,
+                    minutes: *minutes,
+                    relative: *relative,
+                    date: *date,
+                };
+                self.correct(db, *nth_last, correction, dry_run).await
+            }
+            WorktimeCommand::Add { date, start, end } => {
+                self.add(db, date.and_time(*start), date.and_time(*end))
+                    .await
+            }
+            WorktimeCommand::Balance { since } => self.balance(db, *since, clock).await,
+            WorktimeCommand::Holiday { action } => self.holiday(db, action).await,
+            WorktimeCommand::Watch => self.watch(db, clock).await,
+            WorktimeCommand::Continue => self.continue_last(db, clock).await,
+            WorktimeCommand::Cancel { .. } => self.cancel(db).await,
+            WorktimeCommand::Undo => self.undo(db).await,
+            WorktimeCommand::Note { nth_last, text } => self.note(db, *nth_last, text).await,
+            WorktimeCommand::List {
+                limit,
+                offset,
+                tz_shift,
+            } => self.list(db, *limit, *offset, *tz_shift).await,
+            WorktimeCommand::Export { path, from, to } => {
+                self.export(db, path, *from, *to, clock).await
+            }
+            WorktimeCommand::Import {
+                path,
+                skip_overlaps,
+            } => self.import(db, path, *skip_overlaps, dry_run).await,
+            WorktimeCommand::Backup { path, force } => self.backup(db, path, *force).await,
+            WorktimeCommand::Delete { nth_last, .. } => self.delete(db, *nth_last, dry_run).await,
+            WorktimeCommand::Merge {
+                date,
+                first_nth,
+                second_nth,
+                preserve_total,
+            } => match (date, first_nth.zip(*second_nth)) {
+                (Some(date), _) => self.merge(db, *date, *preserve_total).await,
+                (None, Some(pair)) => self.merge_pair(db, pair, *preserve_total).await,
+                (None, None) => Err("Merge needs --date or two sessions".into()),
+            },
+            WorktimeCommand::Split {
+                nth_last,
+                hours,
+                minutes,
+            } => self.split(db, *nth_last, (*hours, *minutes)).await,
+            WorktimeCommand::Sql => self.sqlite(db),
+            WorktimeCommand::Doctor => self.doctor(db).await,
+            WorktimeCommand::Repair { .. } => self.repair(db).await,
+            WorktimeCommand::Completions { shell } => self.completions(*shell),
             WorktimeCommand::InternalHelp => self.help(),
             WorktimeCommand::Quit => Ok("See ya, bruv".to_string()),
-        }
+        };
+        message.map(Into::into)
     }
 
-    async fn status(&self, db: &WorktimeDatabase) -> CommandResult {
-        match db.get_last_session().await? {
-            Some(WorktimeSession {
-                id: _,
-                start: _,
-                end: Some(_),
-            }) => Ok("Not running".to_string()),
+    async fn status(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        let last = db.get_last_session().await?;
+        let status = match &last {
+            Some(WorktimeSession { end: Some(_), .. }) => "Not running".to_string(),
             Some(WorktimeSession {
-                id: _,
+                id,
                 start,
                 end: None,
-            }) => Ok(format!("Running since {start}")),
-            None => Err(CommandError::Other("No previous sessions".to_string())),
+                ..
+            }) => match db.get_open_break(*id).await? {
+                Some(pause) => format!("Paused since {}", display_time(&pause.start)),
+                None => format!("Running since {}", display_time(start)),
+            },
+            None => return Err(CommandError::Other("No previous sessions".to_string())),
+        };
+        let status = with_clock_skew_warning(status, last.as_ref(), clock);
+        with_idle_warning(status, db, last.as_ref(), clock.get_now()).await
+    }
+
+    /// e.g. `Running since 09:00 (2.50h elapsed, 5.00h today)`
+    async fn status_detailed(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        let now = clock.get_now();
+        let today = now.date();
+        let sessions = db.get_sessions_since(today).await?;
+        let today_total = to_hours(tracked_between(&sessions, (today, today), now));
+        let last = db.get_last_session().await?;
+        let status = match &last {
+            Some(WorktimeSession {
+                start, end: None, ..
+            }) => format!(
+                "Running since {} ({:.2}h elapsed, {today_total:.2}h today)",
+                display_time(start),
+                to_hours(now - *start)
+            ),
+            Some(WorktimeSession { end: Some(end), .. }) => format!(
+                "Not running, last stop at {} ({today_total:.2}h today)",
+                display_time(end)
+            ),
+            None => return Err(CommandError::Other("No previous sessions".to_string())),
+        };
+        let status = with_clock_skew_warning(status, last.as_ref(), clock);
+        with_idle_warning(status, db, last.as_ref(), clock.get_now()).await
+    }
+
+    /// never returns `Ok` - runs until interrupted (Ctrl-C)
+    async fn watch_status(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        loop {
+            let frame = self.status_frame(db, clock).await?;
+            // clear screen & move cursor home
+            print!("\x1B[2J\x1B[H{frame}");
+            io::stdout()
+                .flush()
+                .map_err(|e| format!("Failed to draw status: {e}"))?;
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
         }
     }
 
-    async fn start(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
-        db.insert_start(clock)
-            .await
-            .map(|time| format!("Start at {}", display_time(&time)))
+    /// returns once the session is stopped (e.g. from another shell) or on Ctrl-C
+    async fn watch(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        if self.watch_tick(db, clock).await?.is_none() {
+            return Err("No session running".into());
+        }
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    println!();
+                    return Ok("Stopped watching".to_string());
+                }
+                _ = interval.tick() => match self.watch_tick(db, clock).await? {
+                    Some(tick) => {
+                        print!("\r{tick}");
+                        io::stdout()
+                            .flush()
+                            .map_err(|e| format!("Failed to draw elapsed time: {e}"))?;
+                    }
+                    None => {
+                        println!();
+                        return Ok("Session stopped".to_string());
+                    }
+                },
+            }
+        }
     }
 
-    async fn stop(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+    /// e.g. `Elapsed: 02:03:04` - `None` once no session is running
+    pub async fn watch_tick(
+        &self,
+        db: &WorktimeDatabase,
+        clock: &impl Clock,
+    ) -> CommandResult<Option<String>> {
+        Ok(db
+            .get_last_session()
+            .await?
+            .filter(|last| last.is_open())
+            .map(|open| format!("Elapsed: {}", display_elapsed(clock.get_now() - open.start))))
+    }
+
+    /// a single render of [`WorktimeCommand::Status`]'s `--watch` mode
+    pub async fn status_frame(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        let mut frame = self.status(db, clock).await?;
+        if let Some(open) = db.get_last_session().await?.filter(|s| s.is_open()) {
+            let elapsed = clock.get_now() - open.start;
+            frame.push_str(&format!("\nElapsed: {}", display_elapsed(elapsed)));
+        }
+        Ok(frame)
+    }
+
+    /// fast path for widgets polling the state
+    async fn status_json(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        match db.get_last_session().await? {
+            Some(WorktimeSession {
+                start, end: None, ..
+            }) => {
+                // machine readable - ignores the configured time format
+                let since = start.format("%H:%M");
+                let elapsed = (clock.get_now() - start).num_minutes();
+                Ok(format!(
+                    r#"{{"running":true,"since":"{since}","elapsed_minutes":{elapsed}}}"#
+                ))
+            }
+            _ => Ok(r#"{"running":false}"#.to_string()),
+        }
+    }
+
+    async fn start(
+        &self,
+        db: &WorktimeDatabase,
+        (note, project): (Option<&str>, Option<&str>),
+        clock: &impl Clock,
+    ) -> CommandResult<CommandOutput> {
         let last = db.get_last_session().await?;
+        let recent = match CONFIG.start_hints {
+            true => db.get_last_n_sessions_desc(20).await?,
+            false => Vec::new(),
+        };
+        db.insert_start(clock, note, project).await.map(|time| {
+            let mut out = format!("Start at {}", display_time(&time));
+            if let Some(hint) = unusual_start_note(time, &recent) {
+                out.push_str(&format!("\n{hint}"));
+            }
+            CommandOutput::from(with_clock_skew_warning(out, last.as_ref(), clock))
+                .with("time", format!("\"{}\"", time.format("%H:%M:%S")))
+        })
+    }
 
-        if last.is_none() {
+    async fn stop(
+        &self,
+        db: &WorktimeDatabase,
+        threshold_alert: Option<u32>,
+        trim_idle: bool,
+        clock: &impl Clock,
+    ) -> CommandResult {
+        let Some(last) = db.get_last_session().await? else {
             return Err("No previous sessions".into());
-        }
-        let last = last.unwrap();
+        };
         if last.end.is_some() {
             return Err("No session started".into());
         }
 
-        db.insert_stop(last.id, clock)
-            .await
-            .map(|time| format!("Stop at {}", display_time(&time)))
-            .map_err(|e| e.into())
+        let last_seen = match trim_idle {
+            true => db.get_last_seen(last.id).await?,
+            false => None,
+        };
+        // read before stopping, which closes a break that would suppress it
+        let idle = match last_seen {
+            Some(_) => String::new(),
+            None => with_idle_warning(String::new(), db, Some(&last), clock.get_now()).await?,
+        };
+        let end = match last_seen {
+            Some(last_seen) => db.stop_at(last.id, last_seen).await?,
+            None => db.insert_stop(last.id, clock).await?,
+        };
+        let mut out = format!("Stop at {}", display_time(&end));
+        out = with_clock_skew_warning(out, Some(&last), clock);
+        out.push_str(&idle);
+
+        if let Some(minutes) = threshold_alert {
+            let stopped = WorktimeSession::new(last.id, last.start, Some(end));
+            let threshold = TimeDelta::minutes(minutes.into());
+            for alert in sessions_exceeding(&[stopped], threshold, end) {
+                out.push_str(&format!("\n{}", display_threshold_alert(alert, threshold)));
+            }
+        }
+
+        Ok(out)
+    }
+
+    async fn pause(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        let open = db
+            .get_last_session()
+            .await?
+            .filter(|s| s.is_open())
+            .ok_or("No session started")?;
+        if db.get_open_break(open.id).await?.is_some() {
+            return Err("Session already paused".into());
+        }
+        let time = db.insert_pause(open.id, clock).await?;
+        Ok(format!("Pause at {}", display_time(&time)))
+    }
+
+    async fn resume(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        let open = db
+            .get_last_session()
+            .await?
+            .filter(|s| s.is_open())
+            .ok_or("No session started")?;
+        if db.get_open_break(open.id).await?.is_none() {
+            return Err("Session isn't paused".into());
+        }
+        let time = db.insert_resume(open.id, clock).await?;
+        Ok(format!("Resume at {}", display_time(&time)))
     }
 
     async fn report(
         &self,
         db: &WorktimeDatabase,
         kind: ReportKind,
+        options: &ReportOptions,
         clock: &impl Clock,
-    ) -> CommandResult {
-        let ref_day = match kind {
-            ReportKind::Day => get_today(clock),
-            ReportKind::Week => get_week_start(clock),
-            ReportKind::Month => get_month_start(clock),
+    ) -> CommandResult<CommandOutput> {
+        if options.from_stdin {
+            return dates_report(db, io::stdin().lock(), clock.get_now())
+                .await
+                .map(Into::into);
+        }
+
+        let today = get_today(clock);
+        let ref_day = match (options.since, kind, options.anchor) {
+            (Some(since), _, _) => since,
+            _ if options.since_first || options.until.is_some() => db
+                .get_first_session()
+                .await?
+                .map(|first| first.start.date())
+                .unwrap_or(today),
+            (None, ReportKind::Day, anchor) => anchor.unwrap_or(today),
+            (None, ReportKind::Week, Some(anchor)) => week_start_of(anchor, CONFIG.week_start),
+            (None, ReportKind::Week, None) => get_week_start(clock, CONFIG.week_start),
+            (None, ReportKind::Month, Some(anchor)) => month_start_of(anchor),
+            (None, ReportKind::Month, None) => get_month_start(clock),
+            (None, ReportKind::Year, Some(anchor)) => year_start_of(anchor),
+            (None, ReportKind::Year, None) => get_year_start(clock),
+        };
+        let period_end = match (options.until, kind) {
+            (Some(until), _) => until,
+            _ if options.since_first || options.since.is_some() => today,
+            (None, ReportKind::Day) => ref_day,
+            (None, ReportKind::Week) => ref_day + Days::new(6),
+            (None, ReportKind::Month) => ref_day + Months::new(1) - Days::new(1),
+            (None, ReportKind::Year) => ref_day + Months::new(12) - Days::new(1),
+        };
+        let to = period_end.min(today);
+        tracing::debug!(%ref_day, %period_end, %to, "report range");
+        let mut sessions = if options.is_range() {
+            db.get_sessions_between(ref_day, period_end).await?
+        } else {
+            let mut sessions = db.get_sessions_since(ref_day).await?;
+            sessions.retain(|s| s.start.date() <= period_end);
+            sessions
+        };
+        sessions.retain(|s| !options.exclude_sessions.contains(&s.id.into()));
+        if !options.weekday_mask.is_empty() {
+            sessions.retain(|s| options.weekday_mask.contains(&s.start.weekday()));
+        }
+        if let Some(project) = &options.project {
+            sessions.retain(|s| s.project.as_ref() == Some(project));
+        }
+        if options.normalize_open {
+            for open in sessions.iter_mut().filter(|s| s.is_open()) {
+                open.end = Some(open.start);
+            }
+        }
+        let now = clock.get_now();
+        let mut too_short = 0;
+        if let Some(minutes) = options.min_session {
+            let min = TimeDelta::minutes(minutes.into());
+            let before = sessions.len();
+            sessions.retain(|s| s.duration(now) >= min);
+            too_short = before - sessions.len();
+        }
+        tracing::debug!(
+            sessions = ?sessions.iter().map(|s| s.id).collect::<Vec<_>>(),
+            "aggregating"
+        );
+        let tracked = tracked_between(&sessions, (ref_day, to), now);
+        if options.omit_empty_total_zero && tracked.is_zero() {
+            // no `hours` either, so `--format plain` prints nothing as well
+            return Ok(CommandOutput::default());
+        }
+
+        let mut holidays = db.get_holidays_between(ref_day, period_end).await?;
+        holidays.extend(
+            CONFIG
+                .holidays
+                .iter()
+                .filter(|day| (ref_day..=period_end).contains(*day))
+                .map(|day| (*day, HolidayKind::Public)),
+        );
+        let daily = match kind {
+            ReportKind::Month if !options.since_first && !options.is_range() => {
+                Some(worked_days(&sessions, (ref_day, to), now))
+            }
+            _ => None,
+        };
+
+        let (worked, expected, _) = hours_vs_target(
+            kind,
+            reported_delta(&sessions, (ref_day, to), options, now),
+            (ref_day, to),
+            options,
+            &holidays,
+        );
+        let report = match (options.output_format, options.weekly_overtime_only) {
+            _ if options.balance_only => display_signed_hours(worked - expected, options),
+            (_, Some(weekly_target)) => {
+                let overtime: f64 = weekly_totals(&sessions, ref_day, to, now)
+                    .into_iter()
+                    .map(|(_, delta, days)| to_hours(delta) - week_target(weekly_target, days))
+                    .filter(|balance| *balance > 0f64)
+                    .sum();
+                format!("Weekly overtime: {}", display_hours(overtime, options))
+            }
+            _ if options.by_project || options.split_by == Some(ReportSplit::Project) => {
+                let projects = project_totals(&sessions, (ref_day, to), now);
+                let hours: Vec<f64> = projects.iter().map(|(_, hours)| *hours).collect();
+                projects
+                    .iter()
+                    .zip(percent_shares(&hours))
+                    .map(|((project, hours), share)| {
+                        let figure = display_hours(*hours, options);
+                        match options.with_percent {
+                            true => format!("{project}: {figure} ({share}%)"),
+                            false => format!("{project}: {figure}"),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" / ")
+            }
+            _ if options.weekly_rollup_csv => {
+                let weeks = weekly_totals(&sessions, ref_day, to, now);
+                weekly_rollup_csv(&weeks)
+            }
+            (output_format, None) => {
+                let data = ReportData {
+                    period: match (options.since_first, options.is_range()) {
+                        (true, _) => "lifetime".to_string(),
+                        (false, true) => "range".to_string(),
+                        (false, false) => kind.to_string().to_lowercase(),
+                    },
+                    tracked,
+                    sessions: sessions.len(),
+                };
+                format::render(output_format, &data, || {
+                    let mut report = text_report(
+                        kind,
+                        &sessions,
+                        (ref_day, to),
+                        options,
+                        daily.as_deref(),
+                        &holidays,
+                        now,
+                    );
+                    if let Some(minutes) = options.min_session.filter(|_| too_short > 0) {
+                        report.push_str(&format!(
+                            "\n{too_short} session(s) shorter than {minutes}min filtered"
+                        ));
+                    }
+                    report
+                })
+            }
+        };
+
+        let hours = to_hours(tracked);
+        let table = if options.table {
+            // durations stay the same as long as `now` moves along
+            let shift = TimeDelta::minutes(options.tz_shift.unwrap_or(0).into());
+            let shown: Vec<WorktimeSession> = sessions
+                .iter()
+                .map(|session| shifted_for_display(session, options.tz_shift))
+                .collect();
+            session_table(&shown, worked - expected, now + shift)
+        } else {
+            Vec::new()
         };
-        let sessions = db.get_sessions_since(ref_day).await?;
-        let delta = aggregate_session_times(&sessions, clock.get_now());
-        let hours = delta.num_minutes() as f64 / 60f64;
-        Ok(format!("{kind:?}'s balance: {hours:.2}h"))
+        let message = match &options.output {
+            Some(path) => write_report(path, &report, options.append, now)
+                .map(|()| format!("Report written to {}", path.display()))
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?,
+            None => report,
+        };
+        Ok(CommandOutput::from(message)
+            .with("hours", format!("{hours:.2}"))
+            .with_table(table))
+    }
+
+    async fn merge(
+        &self,
+        db: &WorktimeDatabase,
+        date: NaiveDate,
+        preserve_total: bool,
+    ) -> CommandResult {
+        let (merged, count) = db.merge_sessions_on(date, preserve_total).await?;
+        Ok(format!("Merged {count} sessions of {date} into {merged}"))
+    }
+
+    async fn merge_pair(
+        &self,
+        db: &WorktimeDatabase,
+        (first_nth, second_nth): (u32, u32),
+        preserve_total: bool,
+    ) -> CommandResult {
+        if first_nth == second_nth {
+            return Err("Can't merge a session with itself".into());
+        }
+        let first = db.get_nth_last_session(first_nth).await?;
+        let second = db.get_nth_last_session(second_nth).await?;
+        let max_gap = TimeDelta::minutes(CONFIG.merge_gap_minutes.into());
+        let merged = db
+            .merge_sessions(first.id, second.id, max_gap, preserve_total)
+            .await?;
+        Ok(format!(
+            "Merged '{}' and '{}' into {merged}",
+            first.id, second.id
+        ))
     }
 
-    fn sqlite(&self) -> CommandResult {
-        match Command::new("sqlite3").arg(DB_FILE_PATH.deref()).spawn() {
+    async fn split(
+        &self,
+        db: &WorktimeDatabase,
+        nth_last: u32,
+        (hours, minutes): (u8, u8),
+    ) -> CommandResult {
+        let session = db.get_nth_last_session(nth_last).await?;
+        let at = NaiveTime::from_hms_opt(hours.into(), minutes.into(), 0)
+            .map(|time| session.start.date().and_time(time))
+            .ok_or_else(|| format!("Invalid time {hours}:{minutes}"))?;
+        if at <= session.start || session.end.is_some_and(|end| at >= end) {
+            return Err(format!("{} isn't within session '{session}'", display_time(&at)).into());
+        }
+        let second = db.split_session(session.id, at).await?;
+        Ok(format!(
+            "Split '{}' at {} into '{}' and '{second}'",
+            session.id,
+            display_time(&at),
+            session.id
+        ))
+    }
+
+    fn sqlite(&self, db: &WorktimeDatabase) -> CommandResult {
+        let path = db.path().ok_or("The database isn't a file")?;
+        match Command::new("sqlite3").arg(path).spawn() {
             Ok(mut child) => match child.wait() {
                 Ok(_) => Ok(String::default()),
                 Err(_) => Err("Failed to wait on sqlite3".into()),
@@ -192,8 +1164,73 @@ impl WorktimeCommand {
         }
     }
 
+    async fn repair(&self, db: &WorktimeDatabase) -> CommandResult {
+        match db.close_stale_open_sessions().await? {
+            0 => Ok("Nothing to repair".to_string()),
+            n => Ok(format!(
+                "Closed {n} stale running session(s) at their start; the latest keeps running"
+            )),
+        }
+    }
+
+    async fn doctor(&self, db: &WorktimeDatabase) -> CommandResult {
+        let mut checks = Vec::new();
+
+        checks.push(match db.path().map(|path| (path, fs::metadata(path))) {
+            Some((path, Ok(meta))) if !meta.permissions().readonly() => {
+                ("pass", format!("database {} is writable", path.display()))
+            }
+            Some((path, Ok(_))) => ("fail", format!("database {} is read-only", path.display())),
+            Some((path, Err(e))) => (
+                "warn",
+                format!("database {} not found: {e}", path.display()),
+            ),
+            None => ("warn", "database is in memory".to_string()),
+        });
+
+        let latest = MIGRATOR.iter().map(|m| m.version).max();
+        checks.push(match db.get_schema_version().await {
+            Ok(applied) if applied == latest => (
+                "pass",
+                format!("migrations are current (version {})", applied.unwrap_or(0)),
+            ),
+            Ok(applied) => (
+                "fail",
+                format!("migrations not current (applied {applied:?}, latest {latest:?})"),
+            ),
+            Err(e) => ("fail", format!("can't read schema version: {e}")),
+        });
+
+        checks.push(match Command::new("sqlite3").arg("--version").output() {
+            Ok(out) if out.status.success() => ("pass", "sqlite3 is installed".to_string()),
+            _ => (
+                "warn",
+                "sqlite3 not found in $PATH (only needed for `sql`)".to_string(),
+            ),
+        });
+
+        checks.push(match db.check_integrity().await {
+            Ok(problems) if problems.is_empty() => ("pass", "no corrupt data".to_string()),
+            Ok(problems) => ("fail", problems.join("; ")),
+            Err(e) => ("fail", format!("can't check data: {e}")),
+        });
+
+        Ok(checks
+            .iter()
+            .map(|(level, check)| format!("[{level}] {check}"))
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
+    fn completions(&self, shell: Shell) -> CommandResult {
+        let mut cmd = Cli::command();
+        let mut buf = Vec::new();
+        clap_complete::generate(shell, &mut cmd, "worktime", &mut buf);
+        String::from_utf8(buf).map_err(|e| e.to_string().into())
+    }
+
     fn help(&self) -> CommandResult {
-        let styled = <Cli as clap::CommandFactory>::command().render_help();
+        let styled = Cli::command().render_help();
         Ok(format!("{styled}"))
     }
 
@@ -201,30 +1238,990 @@ impl WorktimeCommand {
         &self,
         db: &WorktimeDatabase,
         nth_last: u32,
-        kind: CorrectionKind,
-        hours: u8,
-        minutes: u8,
+        correction: Correction,
+        dry_run: bool,
     ) -> Result<String, CommandError> {
-        let session = db.get_nth_last_session(nth_last).await?;
+        let Correction {
+            kind,
+            hours,
+            minutes,
+            relative,
+            date,
+        } = correction;
+        let mut session = db.get_nth_last_session(nth_last).await?;
         let id = session.id;
+        let shift = date.map_or(TimeDelta::zero(), |day| day - session.start.date());
+        session.start += shift;
+        session.end = session.end.map(|end| end + shift);
 
-        let date_time = session.start.date().and_time(
-            NaiveTime::from_hms_opt(hours as u32, minutes as u32, 0).expect("cannot build time"),
-        );
+        let date_time = if relative {
+            let current = match kind {
+                CorrectionKind::Start => session.start,
+                CorrectionKind::End => session
+                    .end
+                    .ok_or_else(|| format!("Session '{id}' has no end yet"))?,
+            };
+            current + TimeDelta::hours(hours.into()) + TimeDelta::minutes(minutes.into())
+        } else {
+            let time = u32::try_from(hours)
+                .ok()
+                .zip(u32::try_from(minutes).ok())
+                .and_then(|(h, m)| NaiveTime::from_hms_opt(h, m, 0))
+                .ok_or_else(|| format!("Invalid time {hours}:{minutes}"))?;
+            session.start.date().and_time(time)
+        };
 
         match kind {
-            CorrectionKind::Start => Ok(db.update_start_time(id, &date_time).await.map(|()| {
-                format!(
-                    "Start time of '{id}' has been updated to '{}'",
-                    display_time(&date_time)
-                )
-            })?),
-            CorrectionKind::End => Ok(db.update_end_time(id, &date_time).await.map(|()| {
-                format!(
-                    "End time of '{id}' has been updated to '{}'",
-                    display_time(&date_time)
-                )
-            })?),
+            CorrectionKind::Start if session.end.is_some_and(|end| date_time > end) => {
+                return Err("Start can't be after end!".into());
+            }
+            CorrectionKind::End if date_time < session.start => {
+                return Err("End can't be before start!".into());
+            }
+            _ => {}
         }
+        let (start, end) = match kind {
+            CorrectionKind::Start => (date_time, session.end),
+            CorrectionKind::End => (session.start, Some(date_time)),
+        };
+        // the day before, for a session running over midnight into it
+        let neighbours = db.get_sessions_since(start.date() - Days::new(1)).await?;
+        if let Some(other) = neighbours
+            .iter()
+            .filter(|other| other.id != id)
+            .find(|other| other.overlaps(start, end.unwrap_or(NaiveDateTime::MAX)))
+        {
+            return Err(format!("Would overlap session '{other}'").into());
+        }
+        let moved = match date {
+            Some(day) => format!("Moved '{id}' to {day}, "),
+            None => String::new(),
+        };
+        if dry_run {
+            return Ok(format!(
+                "Would {}update {} time of '{id}' to '{}'",
+                date.map_or(String::new(), |day| format!("move '{id}' to {day} and ")),
+                kind.to_string().to_lowercase(),
+                display_time(&date_time)
+            ));
+        }
+        db.correct_session(id, shift, start, end).await?;
+        let field = match kind {
+            CorrectionKind::Start => "Start",
+            CorrectionKind::End => "End",
+        };
+        Ok(format!(
+            "{moved}{field} time of '{id}' has been updated to '{}'",
+            display_time(&date_time)
+        ))
+    }
+
+    async fn add(
+        &self,
+        db: &WorktimeDatabase,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> CommandResult {
+        db.insert_full_session(start, end).await?;
+        Ok(format!(
+            "Added session on {} from {} to {}",
+            start.date(),
+            display_time(&start),
+            display_time(&end)
+        ))
+    }
+
+    async fn cancel(&self, db: &WorktimeDatabase) -> CommandResult {
+        let open = db
+            .get_last_session()
+            .await?
+            .filter(|s| s.is_open())
+            .ok_or("No running session to cancel")?;
+        db.delete_session(open.id).await?;
+        Ok(format!(
+            "Cancelled session started at {}",
+            display_time(&open.start)
+        ))
+    }
+
+    async fn balance(
+        &self,
+        db: &WorktimeDatabase,
+        since: Option<NaiveDate>,
+        clock: &impl Clock,
+    ) -> CommandResult {
+        let today = get_today(clock);
+        let from = match since.or(CONFIG.flex_start) {
+            Some(day) => day,
+            None => db
+                .get_first_session()
+                .await?
+                .map_or(today, |first| first.start.date()),
+        };
+        let sessions = db.get_sessions_since(from).await?;
+        let daily = worked_days(&sessions, (from, today), clock.get_now());
+        let mut holidays: Vec<NaiveDate> = db
+            .get_holidays_between(from, today)
+            .await?
+            .into_iter()
+            .map(|(day, _)| day)
+            .collect();
+        holidays.extend(&CONFIG.holidays);
+        let balance = flex_balance(&daily, (from, today), CONFIG.daily_target, &holidays);
+        Ok(format!("Flex balance since {from}: {balance:+.2}h"))
+    }
+
+    async fn holiday(&self, db: &WorktimeDatabase, action: &HolidayAction) -> CommandResult {
+        match *action {
+            HolidayAction::Add { date, kind } => {
+                db.insert_holiday(date, kind).await?;
+                Ok(format!("Marked {date} as {kind}"))
+            }
+            HolidayAction::Remove { date } => match db.delete_holiday(date).await {
+                Ok(()) => Ok(format!("Removed holiday on {date}")),
+                Err(sqlx::Error::RowNotFound) => Err(format!("No holiday on {date}").into()),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+
+    /// the stop-to-now gap counts as worked - it's one continuous session again
+    async fn continue_last(&self, db: &WorktimeDatabase, clock: &impl Clock) -> CommandResult {
+        let last = db
+            .get_last_session()
+            .await?
+            .ok_or("No session to continue")?;
+        let Some(end) = last.end else {
+            return Err("Session still running".into());
+        };
+        let grace = TimeDelta::minutes(CONFIG.continue_grace_minutes.into());
+        if clock.get_now() - end > grace {
+            return Err(format!(
+                "Stopped at {} - more than {}min ago, use `start` instead",
+                display_time(&end),
+                grace.num_minutes()
+            )
+            .into());
+        }
+        db.reopen_session(last.id, &end).await?;
+        Ok(format!(
+            "Continue session started at {} (stopped at {})",
+            display_time(&last.start),
+            display_time(&end)
+        ))
+    }
+
+    async fn undo(&self, db: &WorktimeDatabase) -> CommandResult {
+        let last = db.get_last_session().await?.ok_or("Nothing to undo")?;
+        let id = last.id;
+        match last.end {
+            Some(end) => {
+                db.reopen_session(id, &end).await?;
+                Ok(format!(
+                    "Reopened session {id} (removed stop at {})",
+                    display_time(&end)
+                ))
+            }
+            None => {
+                db.delete_session(id).await?;
+                Ok(format!(
+                    "Removed session {id} (started at {})",
+                    display_time(&last.start)
+                ))
+            }
+        }
+    }
+
+    async fn note(&self, db: &WorktimeDatabase, nth_last: u32, text: &str) -> CommandResult {
+        let session = db.get_nth_last_session(nth_last).await?;
+        db.update_note(session.id, text).await?;
+        Ok(format!(
+            "Note of '{}' has been updated to '{text}'",
+            session.id
+        ))
+    }
+
+    async fn list(
+        &self,
+        db: &WorktimeDatabase,
+        limit: u32,
+        offset: u32,
+        tz_shift: Option<i32>,
+    ) -> CommandResult {
+        let page = db.get_sessions_paged(limit, offset).await?;
+        if page.is_empty() {
+            return Ok(match offset {
+                0 => "No sessions yet".to_string(),
+                _ => "No more sessions".to_string(),
+            });
+        }
+        Ok(page
+            .iter()
+            .map(|session| shifted_for_display(session, tz_shift).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    async fn export(
+        &self,
+        db: &WorktimeDatabase,
+        path: &Path,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        clock: &impl Clock,
+    ) -> CommandResult {
+        let from = from.unwrap_or(DateTime::UNIX_EPOCH.date_naive());
+        let to = to.unwrap_or(get_today(clock));
+        let sessions = db.get_sessions_between(from, to).await?;
+        let csv = sessions_csv(&sessions, clock.get_now());
+        fs::write(path, csv)
+            .map(|()| format!("Exported {} sessions to {}", sessions.len(), path.display()))
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()).into())
+    }
+
+    async fn import(
+        &self,
+        db: &WorktimeDatabase,
+        path: &Path,
+        skip_overlaps: bool,
+        dry_run: bool,
+    ) -> CommandResult {
+        let csv = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let mut lines = Vec::new();
+        let mut sessions = Vec::new();
+        let mut malformed = Vec::new();
+        for (i, line) in csv.lines().enumerate() {
+            let line_no = i + 1;
+            if line.trim().is_empty() || (line_no == 1 && line.starts_with("id,")) {
+                continue;
+            }
+            match parse_csv_session(line) {
+                Ok(session) => {
+                    lines.push(line_no);
+                    sessions.push(session);
+                }
+                Err(reason) => malformed.push(format!("line {line_no}: {reason}")),
+            }
+        }
+
+        let overlapping = db.find_overlapping(&sessions).await?;
+        if let Some(first) = overlapping.first().filter(|_| !skip_overlaps) {
+            return Err(format!(
+                "Line {} overlaps a recorded session - nothing imported (see --skip-overlaps)",
+                lines[*first]
+            )
+            .into());
+        }
+        let accepted: Vec<_> = sessions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !overlapping.contains(i))
+            .map(|(_, session)| *session)
+            .collect();
+        if !dry_run {
+            db.insert_full_sessions(&accepted).await?;
+        }
+
+        let mut summary = format!(
+            "{} {}, skipped {} (overlap), {} malformed",
+            if dry_run { "Would import" } else { "Imported" },
+            accepted.len(),
+            overlapping.len(),
+            malformed.len()
+        );
+        for reason in malformed {
+            summary.push_str(&format!("\n{reason}"));
+        }
+        Ok(summary)
+    }
+
+    async fn backup(&self, db: &WorktimeDatabase, path: &Path, force: bool) -> CommandResult {
+        if path.exists() && !force {
+            return Err(format!("{} already exists, use --force", path.display()).into());
+        }
+        // `VACUUM INTO` won't overwrite, and the old backup should survive a failed one anyway
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let tmp = path.with_file_name(format!(".{name}.tmp"));
+        let _ = fs::remove_file(&tmp);
+        if let Err(e) = db.backup_to(&tmp).await {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))?;
+        let bytes = fs::metadata(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+            .len();
+        Ok(format!("Backed up to {} ({bytes} bytes)", path.display()))
+    }
+
+    async fn delete(&self, db: &WorktimeDatabase, nth_last: u32, dry_run: bool) -> CommandResult {
+        let session = db.get_nth_last_session(nth_last).await?;
+        if dry_run {
+            return Ok(format!("Would delete session '{session}'"));
+        }
+        db.delete_session(session.id).await?;
+        Ok(format!("Deleted session '{session}'"))
+    }
+}
+
+fn text_report(
+    kind: ReportKind,
+    sessions: &[WorktimeSession],
+    (ref_day, to): (NaiveDate, NaiveDate),
+    options: &ReportOptions,
+    daily: Option<&[(NaiveDate, TimeDelta)]>,
+    holidays: &[(NaiveDate, HolidayKind)],
+    now: NaiveDateTime,
+) -> String {
+    let delta = reported_delta(sessions, (ref_day, to), options, now);
+    let (hours, expected, days_off) =
+        hours_vs_target(kind, delta, (ref_day, to), options, holidays);
+    let vs_target = display_signed_hours(hours - expected, options);
+    let mut report = if options.since_first {
+        format!(
+            "Lifetime balance: {} ({vs_target} vs target)",
+            display_hours(hours, options)
+        )
+    } else if options.is_range() {
+        format!(
+            "{ref_day} to {to} balance: {} ({vs_target} vs target)",
+            display_hours(hours, options)
+        )
+    } else {
+        format!(
+            "{kind:?}'s balance: {} ({vs_target} vs target)",
+            display_hours(hours, options)
+        )
+    };
+
+    if let Some(workweek) = options.workweek_hours {
+        let percent = if workweek > 0f64 {
+            hours / workweek * 100f64
+        } else {
+            0f64
+        };
+        report.push_str(&format!(" ({percent:.0}% of {workweek}h)"));
+    }
+
+    if options.include_note_summary {
+        report.push_str(&display_note_summary(sessions));
+    }
+
+    if days_off > 0 {
+        report.push_str(&format!("\n{days_off} holiday(s) included"));
+    }
+
+    if let Some(days) = daily {
+        report.push_str(&format!("\n{}", display_days_worked(days)));
+    }
+    if matches!(kind, ReportKind::Week) && !options.since_first && !options.is_range() {
+        let mut days = daily_totals(sessions, ref_day, to, now);
+        if let Some(minutes) = options.round_each_day {
+            for (_, day) in &mut days {
+                *day = round_up_delta(*day, TimeDelta::minutes(minutes.into()));
+            }
+        }
+        report.push_str(&format!(
+            "\n{}",
+            display_weekday_breakdown(&days, ref_day, holidays, options)
+        ));
+    }
+
+    if options.net_of_breaks_detail {
+        let rows = breaks_detail(sessions, (ref_day, to), now);
+        report.push_str(&format!("\n{}", render_table(&rows, false)));
+    }
+
+    if options.working_days_only {
+        let days = working_days_between((ref_day, to), holidays);
+        let avg = if days == 0 { 0f64 } else { hours / days as f64 };
+        report.push_str(&format!("\navg/day: {avg:.2}h over {days} working days"));
+    }
+
+    if options.stats {
+        let stats = match session_stats(sessions, now) {
+            Some(s) => format!(
+                "longest: {:.2}h, shortest: {:.2}h, avg: {:.2}h, sessions: {}",
+                to_hours(s.longest),
+                to_hours(s.shortest),
+                to_hours(s.average),
+                s.count
+            ),
+            None => "sessions: 0".to_string(),
+        };
+        report.push_str(&format!("\n{stats}"));
+    }
+
+    if let Some(minutes) = options.threshold_alert {
+        let threshold = TimeDelta::minutes(minutes.into());
+        for alert in sessions_exceeding(sessions, threshold, now) {
+            report.push_str(&format!("\n{}", display_threshold_alert(alert, threshold)));
+        }
+    }
+
+    if let Some(minutes) = options.max_gap_alert {
+        let threshold = TimeDelta::minutes(minutes.into());
+        for (before, after, gap) in compute_gaps(sessions)
+            .into_iter()
+            .filter(|(_, _, gap)| *gap > threshold)
+        {
+            report.push_str(&format!(
+                "\nWarning: {:.2}h gap between session '{before}' and '{after}' (threshold {:.2}h)",
+                to_hours(gap),
+                to_hours(threshold)
+            ));
+        }
+    }
+
+    if let Some(goal_hours) = options.goal_eta {
+        let goal = TimeDelta::minutes((goal_hours * 60f64) as i64);
+        let days = working_days_between((ref_day, to), holidays).max(1);
+        let pace = delta / days as i32;
+        let left = to_hours(goal - delta);
+        let eta = match goal_eta_days(delta, goal, pace) {
+            Some(0) => "met".to_string(),
+            Some(n) => format!(
+                "{left:.2}h left, ~{n} days at {:.2}h/day ({})",
+                to_hours(pace),
+                add_working_days(to, n).format("%a %Y-%m-%d")
+            ),
+            None => format!("{left:.2}h left, no pace yet"),
+        };
+        report.push_str(&format!("\ngoal {goal_hours:.2}h: {eta}"));
+    }
+
+    if options.histogram_hours {
+        let buckets = minutes_per_hour_of_day(sessions, now);
+        for (hour, minutes) in buckets.iter().enumerate().filter(|(_, m)| **m > 0) {
+            let bar = "█".repeat((*minutes as usize).div_ceil(5));
+            report.push_str(&format!("\n{hour:02}h {bar} {minutes}m"));
+        }
+    }
+
+    if options.include_running_marker {
+        for session in sessions {
+            report.push_str(&format!(
+                "\n{}",
+                shifted_for_display(session, options.tz_shift)
+            ));
+            if session.is_open() {
+                let elapsed = display_elapsed(now - session.start);
+                report.push_str(&format!(" ⏳ running {elapsed}"));
+            }
+        }
+    }
+
+    if options.sparkline {
+        let hours: Vec<f64> = daily_totals(sessions, ref_day, to, now)
+            .iter()
+            .map(|(_, delta)| to_hours(*delta))
+            .collect();
+        report.push_str(&format!("\n{}", sparkline(&hours)));
+    }
+
+    if let Some(weekly_target) = options.weekly_target_grid {
+        let weeks = weekly_totals(sessions, ref_day, to, now);
+        report.push_str(&display_weekly_target_grid(&weeks, weekly_target));
+    }
+
+    if let Some(weekly_target) = options.compare_target_trend {
+        let weeks = weekly_totals(sessions, ref_day, to, now);
+        report.push_str(&display_target_trend(&weeks, weekly_target));
+    }
+
+    report
+}
+
+/// the tracked time of `[ref_day, to]`, each day rounded up if `--round-each-day` asks for it
+fn reported_delta(
+    sessions: &[WorktimeSession],
+    (ref_day, to): (NaiveDate, NaiveDate),
+    options: &ReportOptions,
+    now: NaiveDateTime,
+) -> TimeDelta {
+    match options.round_each_day {
+        Some(minutes) => daily_totals(sessions, ref_day, to, now)
+            .into_iter()
+            .map(|(_, day)| round_up_delta(day, TimeDelta::minutes(minutes.into())))
+            .sum(),
+        None => tracked_between(sessions, (ref_day, to), now),
+    }
+}
+
+/// working days of `[from, to]` that aren't days off
+fn working_days_between(
+    (from, to): (NaiveDate, NaiveDate),
+    holidays: &[(NaiveDate, HolidayKind)],
+) -> u32 {
+    let mut days_off: Vec<NaiveDate> = holidays
+        .iter()
+        .map(|(day, _)| *day)
+        .filter(|day| (from..=to).contains(day) && is_working_day(*day))
+        .collect();
+    // a day may be marked both in the db and in `WORKTIME_HOLIDAYS`
+    days_off.sort();
+    days_off.dedup();
+    count_working_days(from, to).saturating_sub(days_off.len() as u32)
+}
+
+/// (worked, expected, days off) of the report period in hours; a week expects its full five
+/// days, the other periods only the days so far, and days off on working days expect nothing;
+/// `--target-from-schedule` expects `WORKTIME_SCHEDULE`'s hours per weekday instead
+fn hours_vs_target(
+    kind: ReportKind,
+    delta: TimeDelta,
+    (ref_day, to): (NaiveDate, NaiveDate),
+    options: &ReportOptions,
+    holidays: &[(NaiveDate, HolidayKind)],
+) -> (f64, f64, usize) {
+    let (expected_from, expected_to) = match kind {
+        ReportKind::Week if !options.since_first && !options.is_range() => {
+            (ref_day, ref_day + Days::new(6))
+        }
+        _ => (ref_day, to),
+    };
+    if options.target_from_schedule {
+        let scheduled = |day| scheduled_hours(&CONFIG.schedule, (day, day), &[]);
+        let days_off: Vec<(NaiveDate, HolidayKind)> = holidays
+            .iter()
+            .copied()
+            .filter(|(day, _)| {
+                (expected_from..=expected_to).contains(day) && scheduled(*day) > 0f64
+            })
+            .collect();
+        let (credited, off): (Vec<_>, Vec<_>) = days_off
+            .iter()
+            .partition(|(_, kind)| CONFIG.credit_leave && *kind != HolidayKind::Public);
+        let hours = to_hours(delta) + credited.iter().map(|(day, _)| scheduled(*day)).sum::<f64>();
+        let off: Vec<NaiveDate> = off.iter().map(|(day, _)| *day).collect();
+        let expected = scheduled_hours(&CONFIG.schedule, (expected_from, expected_to), &off);
+        return (hours, expected, days_off.len());
+    }
+    let days_off: Vec<HolidayKind> = holidays
+        .iter()
+        .filter(|(day, _)| (expected_from..=expected_to).contains(day) && is_working_day(*day))
+        .map(|(_, kind)| *kind)
+        .collect();
+    // credited leave stays expected but counts as worked
+    let credited = days_off
+        .iter()
+        .filter(|kind| CONFIG.credit_leave && **kind != HolidayKind::Public)
+        .count();
+    let hours = to_hours(delta) + credited as f64 * CONFIG.daily_target;
+    let expected = expected_hours(CONFIG.daily_target, expected_from, expected_to)
+        - (days_off.len() - credited) as f64 * CONFIG.daily_target;
+    (hours, expected, days_off.len())
+}
+
+/// the hour figure, e.g. `6.00h` (or `6,00h`)
+fn display_hours(hours: f64, options: &ReportOptions) -> String {
+    let figure = format!("{hours:.2}h");
+    if options.decimal_comma {
+        figure.replace('.', ",")
+    } else {
+        figure
+    }
+}
+
+/// the hour figure with an explicit sign, e.g. `+1.50h`
+fn display_signed_hours(hours: f64, options: &ReportOptions) -> String {
+    let figure = display_hours(hours, options);
+    if figure.starts_with('-') {
+        figure
+    } else {
+        format!("+{figure}")
+    }
+}
+
+/// `date: hours` per date read from `input`
+pub async fn dates_report(
+    db: &WorktimeDatabase,
+    input: impl BufRead,
+    now: NaiveDateTime,
+) -> CommandResult {
+    let mut dates = Vec::new();
+    for line in input.lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {e}"))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let date = NaiveDate::parse_from_str(line, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{line}' (use YYYY-MM-DD)"))?;
+        dates.push(date);
+    }
+
+    let mut report = Vec::new();
+    for date in dates {
+        let sessions = db.get_sessions_on(date).await?;
+        let hours = to_hours(tracked_between(&sessions, (date, date), now));
+        report.push(format!("{date}: {hours:.2}h"));
+    }
+    Ok(report.join("\n"))
+}
+
+/// weeks straddling the period only count the tracked time within it
+fn weekly_rollup_csv(weeks: &[(IsoWeek, TimeDelta, u32)]) -> String {
+    let mut csv = "iso_week,year,hours".to_string();
+    for (week, tracked, _) in weeks {
+        csv.push_str(&format!(
+            "\n{},{},{:.2}",
+            week.week(),
+            week.year(),
+            to_hours(*tracked)
+        ));
+    }
+    csv
+}
+
+/// one row per session plus a total colored by `balance` (hours vs target)
+fn session_table(sessions: &[WorktimeSession], balance: f64, now: NaiveDateTime) -> Vec<TableRow> {
+    let header = ["date", "start", "end", "duration"]
+        .map(String::from)
+        .to_vec();
+    let mut rows = vec![TableRow::from(header)];
+    for session in sessions {
+        let duration = aggregate_session_times(slice::from_ref(session), now);
+        rows.push(TableRow::from(vec![
+            session.start.date().to_string(),
+            display_time(&session.start).to_string(),
+            session
+                .end
+                .map_or("running".to_string(), |end| display_time(&end).to_string()),
+            format!("{:.2}h", to_hours(duration)),
+        ]));
+    }
+    let total = aggregate_session_times(sessions, now);
+    rows.push(TableRow {
+        cells: vec![
+            "total".to_string(),
+            String::new(),
+            String::new(),
+            format!("{:.2}h", to_hours(total)),
+        ],
+        tone: if balance >= 0f64 {
+            Tone::Over
+        } else {
+            Tone::Under
+        },
+    });
+    rows
+}
+
+/// `date | gross | breaks | net` per day of `[from, to]` with sessions (by their start)
+fn breaks_detail(
+    sessions: &[WorktimeSession],
+    (from, to): (NaiveDate, NaiveDate),
+    now: NaiveDateTime,
+) -> Vec<TableRow> {
+    let header = ["date", "gross", "breaks", "net"]
+        .map(String::from)
+        .to_vec();
+    let mut rows = vec![TableRow::from(header)];
+    for day in from.iter_days().take_while(|day| *day <= to) {
+        let of_day: Vec<&WorktimeSession> =
+            sessions.iter().filter(|s| s.start.date() == day).collect();
+        if of_day.is_empty() {
+            continue;
+        }
+        let gross: TimeDelta = of_day.iter().map(|s| s.end.unwrap_or(now) - s.start).sum();
+        let net: TimeDelta = of_day.iter().map(|s| s.duration(now)).sum();
+        rows.push(TableRow::from(vec![
+            day.to_string(),
+            format!("{:.2}h", to_hours(gross)),
+            format!("{:.2}h", to_hours(gross - net)),
+            format!("{:.2}h", to_hours(net)),
+        ]));
+    }
+    rows
+}
+
+/// `session` with all its times moved by `tz_shift` minutes - for display only
+fn shifted_for_display(session: &WorktimeSession, tz_shift: Option<i32>) -> WorktimeSession {
+    let by = TimeDelta::minutes(tz_shift.unwrap_or(0).into());
+    WorktimeSession {
+        start: session.start + by,
+        end: session.end.map(|end| end + by),
+        breaks: session
+            .breaks
+            .iter()
+            .map(|pause| WorktimeBreak {
+                start: pause.start + by,
+                end: pause.end.map(|end| end + by),
+            })
+            .collect(),
+        ..session.clone()
+    }
+}
+
+/// the non-empty notes as bullets in chronological order, long ones cut to 60 chars;
+/// nothing without notes
+fn display_note_summary(sessions: &[WorktimeSession]) -> String {
+    const WIDTH: usize = 60;
+    let mut noted: Vec<&WorktimeSession> = sessions.iter().collect();
+    noted.sort_by_key(|s| s.start);
+    noted
+        .iter()
+        .filter_map(|s| s.note.as_deref().map(str::trim))
+        .filter(|note| !note.is_empty())
+        .map(|note| match note.chars().count() > WIDTH {
+            true => format!("\n- {}…", note.chars().take(WIDTH - 1).collect::<String>()),
+            false => format!("\n- {note}"),
+        })
+        .collect()
+}
+
+/// e.g. `days worked: 12, avg/day: 7.30h`
+fn display_days_worked(days: &[(NaiveDate, TimeDelta)]) -> String {
+    let total: TimeDelta = days.iter().map(|(_, delta)| *delta).sum();
+    let avg = match days.len() {
+        0 => 0f64,
+        n => to_hours(total) / n as f64,
+    };
+    format!("days worked: {}, avg/day: {avg:.2}h", days.len())
+}
+
+/// the seven days from `week_start`, e.g. `Mon 8.00h / Tue 0.00h / ... / Total 40.00h`;
+/// with `--annotate-holidays` days off read `Mon 0.00h (holiday)`, `--locale` names the days
+fn display_weekday_breakdown(
+    days: &[(NaiveDate, TimeDelta)],
+    week_start: NaiveDate,
+    holidays: &[(NaiveDate, HolidayKind)],
+    options: &ReportOptions,
+) -> String {
+    let mut total = 0f64;
+    let mut parts: Vec<String> = week_start
+        .iter_days()
+        .take(7)
+        .map(|day| {
+            let hours = days
+                .iter()
+                .find(|(worked, _)| *worked == day)
+                .map_or(0f64, |(_, delta)| to_hours(*delta));
+            total += hours;
+            let name = match options.locale {
+                Some(locale) => day.format_localized("%a", locale).to_string(),
+                None => day.weekday().to_string(),
+            };
+            let off = options.annotate_holidays && holidays.iter().any(|(off, _)| *off == day);
+            match off {
+                true => format!("{name} {} (holiday)", display_hours(hours, options)),
+                false => format!("{name} {}", display_hours(hours, options)),
+            }
+        })
+        .collect();
+    parts.push(format!("Total {}", display_hours(total, options)));
+    parts.join(" / ")
+}
+
+/// clap's parser for `--locale`
+fn locale(name: &str) -> Result<Locale, String> {
+    name.parse()
+        .map_err(|_| format!("unknown locale '{name}' (use e.g. `de_DE`)"))
+}
+
+/// clap's parser for date args (see [`parse_date_spec`])
+fn date_spec(spec: &str) -> Result<NaiveDate, String> {
+    parse_date_spec(spec, &get_clock())
+}
+
+/// the tracked time of `[from, to]`, rounded as configured
+fn tracked_between(
+    sessions: &[WorktimeSession],
+    period: (NaiveDate, NaiveDate),
+    now: NaiveDateTime,
+) -> TimeDelta {
+    let granularity = TimeDelta::minutes(CONFIG.rounding_minutes.into());
+    rounded_session_times_between(sessions, period, now, granularity, CONFIG.round_each)
+}
+
+/// hours per project (sessions without one under `-`), sorted by name
+fn project_totals(
+    sessions: &[WorktimeSession],
+    (from, to): (NaiveDate, NaiveDate),
+    now: NaiveDateTime,
+) -> Vec<(String, f64)> {
+    let mut projects: Vec<String> = sessions
+        .iter()
+        .map(|s| s.project.clone().unwrap_or("-".to_string()))
+        .collect();
+    projects.sort();
+    projects.dedup();
+    projects
+        .into_iter()
+        .map(|project| {
+            let of_project: Vec<WorktimeSession> = sessions
+                .iter()
+                .filter(|s| s.project.as_deref().unwrap_or("-") == project)
+                .cloned()
+                .collect();
+            let hours = to_hours(tracked_between(&of_project, (from, to), now));
+            (project, hours)
+        })
+        .collect()
+}
+
+/// open sessions have an empty `end` and last until `now`
+fn sessions_csv(sessions: &[WorktimeSession], now: NaiveDateTime) -> String {
+    let mut csv = String::from("id,date,start,end,duration_minutes\n");
+    for session in sessions {
+        let minutes = aggregate_session_times(slice::from_ref(session), now).num_minutes();
+        let row = [
+            session.id.to_string(),
+            session.start.format("%Y-%m-%d").to_string(),
+            session.start.format("%H:%M").to_string(),
+            session
+                .end
+                .map(|end| end.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            minutes.to_string(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// `id,date,start,end,duration_minutes` → (start, end); only `date`, `start` & `end` are read,
+/// an `end` before `start` is on the next day (an overnight session as export writes it)
+fn parse_csv_session(line: &str) -> Result<(NaiveDateTime, NaiveDateTime), String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [_, date, start, end, ..] = fields[..] else {
+        return Err(format!("expected 5 columns, got {}", fields.len()));
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{date}'"))?;
+    let time =
+        |t: &str| NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| format!("invalid time '{t}'"));
+    let (start, end) = (date.and_time(time(start)?), date.and_time(time(end)?));
+    match end < start {
+        true => Ok((start, end + Days::new(1))),
+        false => Ok((start, end)),
+    }
+}
+
+/// quotes fields that would break the row
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn write_report(path: &Path, report: &str, append: bool, now: NaiveDateTime) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    if append {
+        writeln!(file, "# {}", now.format("%Y-%m-%d %H:%M"))?;
+    }
+    writeln!(file, "{report}")
+}
+
+fn display_weekly_target_grid(weeks: &[(IsoWeek, TimeDelta, u32)], weekly_target: f64) -> String {
+    let mut grid = format!(
+        "\n{:<10}{:>9}{:>9}{:>9}",
+        "week", "tracked", "target", "balance"
+    );
+    let (mut tracked_sum, mut target_sum) = (0f64, 0f64);
+    for (week, tracked, working_days) in weeks {
+        let tracked = to_hours(*tracked);
+        let target = week_target(weekly_target, *working_days);
+        grid.push_str(&display_grid_row(&display_week(week), tracked, target));
+        tracked_sum += tracked;
+        target_sum += target;
+    }
+    grid.push_str(&display_grid_row("total", tracked_sum, target_sum));
+    grid
+}
+
+fn display_target_trend(weeks: &[(IsoWeek, TimeDelta, u32)], weekly_target: f64) -> String {
+    let mut trend = String::new();
+    let mut previous: Option<f64> = None;
+    for (week, tracked, working_days) in weeks {
+        let balance = to_hours(*tracked) - week_target(weekly_target, *working_days);
+        let arrow = match previous {
+            Some(p) if balance > p => " ↑",
+            Some(p) if balance < p => " ↓",
+            Some(_) => " →",
+            None => "",
+        };
+        trend.push_str(&format!(
+            "\n{:<10}{balance:>+8.2}h{arrow}",
+            display_week(week)
+        ));
+        previous = Some(balance);
+    }
+    trend
+}
+
+/// weeks cut by the period only expect their share of the target
+fn week_target(weekly_target: f64, working_days: u32) -> f64 {
+    weekly_target * working_days as f64 / 5f64
+}
+
+fn display_week(week: &IsoWeek) -> String {
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+fn display_grid_row(label: &str, tracked: f64, target: f64) -> String {
+    let balance = tracked - target;
+    format!("\n{label:<10}{tracked:>8.2}h{target:>8.2}h{balance:>+8.2}h")
+}
+
+/// see [`crate::config::Config::idle_after_minutes`]
+async fn with_idle_warning(
+    mut out: String,
+    db: &WorktimeDatabase,
+    last: Option<&WorktimeSession>,
+    now: NaiveDateTime,
+) -> CommandResult {
+    let Some(open) = last else {
+        return Ok(out);
+    };
+    let threshold = TimeDelta::minutes(CONFIG.idle_after_minutes.into());
+    let warning = idle_since(db, open, now)
+        .await?
+        .and_then(|last_seen| idle_warning(last_seen, now, threshold));
+    if let Some(warning) = warning {
+        out.push_str(&format!("\n{warning}"));
+    }
+    Ok(out)
+}
+
+/// when the running (unpaused) session was last seen, if that's longer ago than
+/// [`crate::config::Config::idle_after_minutes`]
+async fn idle_since(
+    db: &WorktimeDatabase,
+    open: &WorktimeSession,
+    now: NaiveDateTime,
+) -> CommandResult<Option<NaiveDateTime>> {
+    if CONFIG.idle_after_minutes == 0
+        || open.end.is_some()
+        || db.get_open_break(open.id).await?.is_some()
+    {
+        return Ok(None);
+    }
+    let threshold = TimeDelta::minutes(CONFIG.idle_after_minutes.into());
+    Ok(db
+        .get_last_seen(open.id)
+        .await?
+        .filter(|last_seen| now - *last_seen > threshold))
+}
+
+fn with_clock_skew_warning(
+    mut out: String,
+    last: Option<&WorktimeSession>,
+    clock: &impl Clock,
+) -> String {
+    if let Some(warning) = last.and_then(|last| clock_skew_warning(last, clock.get_now())) {
+        out.push_str(&format!("\n{warning}"));
     }
+    out
 }
@@ -0,0 +1,139 @@
+use crate::time::TimeFormat;
+use chrono::{NaiveDate, Weekday};
+use chrono_tz::Tz;
+use std::{env, str::FromStr, sync::LazyLock};
+
+pub static CONFIG: LazyLock<Config> = LazyLock::new(Config::from_env);
+
+/// settings read from `WORKTIME_*` env vars (falling back to the defaults)
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// hours expected per working day (`WORKTIME_DAILY_TARGET`)
+    pub daily_target: f64,
+    /// hours expected per weekday (Mon first) for `--target-from-schedule`, e.g.
+    /// `mon=8,tue=8,wed=8,thu=8,fri=6`, unset = the daily target Mon-Fri (`WORKTIME_SCHEDULE`)
+    pub schedule: [f64; 7],
+    /// first day of a `Week` report, e.g. `sun` (`WORKTIME_WEEK_START`)
+    pub week_start: Weekday,
+    /// reported time is rounded to the nearest this many minutes, 0 = off (`WORKTIME_ROUNDING_MINUTES`)
+    pub rounding_minutes: u32,
+    /// round every session instead of the total (`WORKTIME_ROUND_EACH`)
+    pub round_each: bool,
+    /// IANA zone the clock reads in, e.g. `Europe/Berlin`, unset = system zone (`WORKTIME_TIMEZONE`);
+    /// sessions are stored as wall-clock time of that zone, older ones are read the same way
+    pub timezone: Option<Tz>,
+    /// warn on startup about a session running longer than this (`WORKTIME_STALE_AFTER_HOURS`)
+    pub stale_after_hours: u32,
+    /// `24h` or `12h` clock for printed times (`WORKTIME_TIME_FORMAT`)
+    pub time_format: TimeFormat,
+    /// how long after a stop `continue` may still reopen the session (`WORKTIME_CONTINUE_GRACE_MINUTES`)
+    pub continue_grace_minutes: u32,
+    /// first day of the flex balance, unset = first session (`WORKTIME_FLEX_START`)
+    pub flex_start: Option<NaiveDate>,
+    /// days without a target, e.g. `2025-12-25,2025-12-26` (`WORKTIME_HOLIDAYS`)
+    pub holidays: Vec<NaiveDate>,
+    /// count vacation & sick days as worked instead of not expected (`WORKTIME_CREDIT_LEAVE`)
+    pub credit_leave: bool,
+    /// warn on `status`/`stop` if a long-running worktime (e.g. `watch`) saw nothing of the
+    /// session for this long, 0 = off (`WORKTIME_IDLE_AFTER_MINUTES`)
+    pub idle_after_minutes: u32,
+    /// largest gap between two sessions that `merge` still joins (`WORKTIME_MERGE_GAP_MINUTES`)
+    pub merge_gap_minutes: u32,
+    /// note on `start` when it looks accidental, e.g. at 03:00 (`WORKTIME_START_HINTS`)
+    pub start_hints: bool,
+    /// write-ahead log, so a one-shot `stop` doesn't collide with a running `watch` (`WORKTIME_WAL`)
+    pub wal: bool,
+    /// how long to wait for a lock held by another worktime before failing (`WORKTIME_BUSY_TIMEOUT_MS`)
+    pub busy_timeout_ms: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            daily_target: 8.0,
+            schedule: [8.0, 8.0, 8.0, 8.0, 8.0, 0.0, 0.0],
+            week_start: Weekday::Mon,
+            rounding_minutes: 0,
+            round_each: true,
+            timezone: None,
+            stale_after_hours: 16,
+            time_format: TimeFormat::H24,
+            continue_grace_minutes: 30,
+            flex_start: None,
+            holidays: Vec::new(),
+            credit_leave: false,
+            idle_after_minutes: 0,
+            merge_gap_minutes: 5,
+            start_hints: true,
+            wal: true,
+            busy_timeout_ms: 5000,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let daily_target = env_or("WORKTIME_DAILY_TARGET", default.daily_target);
+        Self {
+            daily_target,
+            schedule: env::var("WORKTIME_SCHEDULE")
+                .ok()
+                .and_then(|v| parse_schedule(&v))
+                .unwrap_or([
+                    daily_target,
+                    daily_target,
+                    daily_target,
+                    daily_target,
+                    daily_target,
+                    0.0,
+                    0.0,
+                ]),
+            week_start: env_or("WORKTIME_WEEK_START", default.week_start),
+            rounding_minutes: env_or("WORKTIME_ROUNDING_MINUTES", default.rounding_minutes),
+            round_each: env_or("WORKTIME_ROUND_EACH", default.round_each),
+            timezone: env::var("WORKTIME_TIMEZONE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or(default.timezone),
+            stale_after_hours: env_or("WORKTIME_STALE_AFTER_HOURS", default.stale_after_hours),
+            time_format: env_or("WORKTIME_TIME_FORMAT", default.time_format),
+            continue_grace_minutes: env_or(
+                "WORKTIME_CONTINUE_GRACE_MINUTES",
+                default.continue_grace_minutes,
+            ),
+            flex_start: env::var("WORKTIME_FLEX_START")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or(default.flex_start),
+            holidays: env::var("WORKTIME_HOLIDAYS")
+                .map(|v| v.split(',').filter_map(|d| d.trim().parse().ok()).collect())
+                .unwrap_or(default.holidays),
+            credit_leave: env_or("WORKTIME_CREDIT_LEAVE", default.credit_leave),
+            idle_after_minutes: env_or("WORKTIME_IDLE_AFTER_MINUTES", default.idle_after_minutes),
+            merge_gap_minutes: env_or("WORKTIME_MERGE_GAP_MINUTES", default.merge_gap_minutes),
+            start_hints: env_or("WORKTIME_START_HINTS", default.start_hints),
+            wal: env_or("WORKTIME_WAL", default.wal),
+            busy_timeout_ms: env_or("WORKTIME_BUSY_TIMEOUT_MS", default.busy_timeout_ms),
+        }
+    }
+}
+
+/// e.g. `mon=8,fri=6`; unlisted days expect nothing, anything unparsable voids it all
+fn parse_schedule(spec: &str) -> Option<[f64; 7]> {
+    let mut hours = [0f64; 7];
+    for entry in spec.split(',') {
+        let (day, target) = entry.split_once('=')?;
+        let day: Weekday = day.trim().parse().ok()?;
+        hours[day.num_days_from_monday() as usize] = target.trim().parse().ok()?;
+    }
+    Some(hours)
+}
+
+/// unset or unparsable vars fall back to `default`
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
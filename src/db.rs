@@ -1,15 +1,21 @@
 use crate::{
     err::CommandResult,
-    time::{Clock, display_time},
+    time::{Clock, aggregate_session_times, display_time},
 };
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
 use sqlx::{Error, SqlitePool};
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+use tokio::task::JoinHandle;
+use tracing::instrument;
 
 type Result<T> = sqlx::Result<T>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct WorktimeSessionId(u32);
+/// sqlite's INTEGER PRIMARY KEY (rowid) covers the full i64 range
+pub struct WorktimeSessionId(i64);
 
 impl Display for WorktimeSessionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -19,17 +25,17 @@ impl Display for WorktimeSessionId {
 
 impl From<i64> for WorktimeSessionId {
     fn from(value: i64) -> Self {
-        WorktimeSessionId(u32::try_from(value).unwrap())
+        WorktimeSessionId(value)
     }
 }
 
 impl From<u32> for WorktimeSessionId {
     fn from(value: u32) -> Self {
-        WorktimeSessionId(value)
+        WorktimeSessionId(value.into())
     }
 }
 
-impl From<WorktimeSessionId> for u32 {
+impl From<WorktimeSessionId> for i64 {
     fn from(value: WorktimeSessionId) -> Self {
         value.0
     }
@@ -40,15 +46,62 @@ pub struct WorktimeSession {
     pub id: WorktimeSessionId,
     pub start: NaiveDateTime,
     pub end: Option<NaiveDateTime>,
+    pub note: Option<String>,
+    pub project: Option<String>,
+    /// only loaded where the worked time matters (see [`WorktimeDatabase::get_sessions_since`])
+    pub breaks: Vec<WorktimeBreak>,
 }
 
 impl WorktimeSession {
     #[allow(dead_code)]
     pub fn new(id: WorktimeSessionId, start: NaiveDateTime, end: Option<NaiveDateTime>) -> Self {
-        Self { id, start, end }
+        Self {
+            id,
+            start,
+            end,
+            note: None,
+            project: None,
+            breaks: Vec::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// worked time minus (loaded) breaks; an open session runs until `now`
+    pub fn duration(&self, now: NaiveDateTime) -> TimeDelta {
+        let end = self.end.unwrap_or(now);
+        let paused: TimeDelta = self
+            .breaks
+            .iter()
+            .map(|pause| pause.end.unwrap_or(end) - pause.start)
+            .sum();
+        end - self.start - paused
+    }
+
+    /// whether `[start, end)` intersects this session (a running one is open-ended)
+    pub fn overlaps(&self, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+        start < self.end.unwrap_or(NaiveDateTime::MAX) && self.start < end
     }
 }
 
+/// a day off; `Public` never expects work, `Vacation`/`Sick` may be credited instead
+/// (see [`crate::config::Config::credit_leave`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, strum::Display, strum::EnumString)]
+pub enum HolidayKind {
+    Public,
+    Vacation,
+    Sick,
+}
+
+/// a pause within a session; `end` is `None` while paused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktimeBreak {
+    pub start: NaiveDateTime,
+    pub end: Option<NaiveDateTime>,
+}
+
 impl Display for WorktimeSession {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let id = &self.id;
@@ -57,19 +110,39 @@ impl Display for WorktimeSession {
             .end
             .map(|t| display_time(&t).to_string())
             .unwrap_or("-".to_string());
-        write!(f, "id: {id};start: {start};end: {end}")
+        write!(f, "id: {id};start: {start};end: {end}")?;
+        match self.note.as_deref().map(str::trim) {
+            Some("") => write!(f, ";note: -"),
+            Some(note) => write!(f, ";note: {note}"),
+            None => Ok(()),
+        }
     }
 }
 
-impl From<(i64, NaiveDateTime, Option<NaiveDateTime>)> for WorktimeSession {
-    fn from((id, start, end): (i64, NaiveDateTime, Option<NaiveDateTime>)) -> Self {
+/// (id, start, end, note, project) as selected from `work_sessions`
+type SessionRow = (
+    i64,
+    NaiveDateTime,
+    Option<NaiveDateTime>,
+    Option<String>,
+    Option<String>,
+);
+
+impl From<SessionRow> for WorktimeSession {
+    fn from((id, start, end, note, project): SessionRow) -> Self {
         let id = WorktimeSessionId::from(id);
-        Self { id, start, end }
+        Self {
+            note,
+            project,
+            ..Self::new(id, start, end)
+        }
     }
 }
 
 pub struct WorktimeDatabase {
     pool: SqlitePool,
+    /// the file behind `pool`, `None` for in-memory dbs
+    path: Option<PathBuf>,
 }
 
 impl WorktimeDatabase {
@@ -78,12 +151,25 @@ impl WorktimeDatabase {
         tokio::spawn(async move {
             let _ = sanity_check(p2).await;
         });
-        Self { pool }
+        Self { pool, path: None }
+    }
+
+    /// remembers the file `pool` was opened on (for `sql` & `doctor`)
+    pub fn with_path(self, path: &Path) -> Self {
+        Self {
+            path: Some(path.to_path_buf()),
+            ..self
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
     }
 
+    #[instrument(level = "debug", skip(self))]
     pub async fn get_last_session(&self) -> Result<Option<WorktimeSession>> {
         let last = sqlx::query!("
-            SELECT id, start_time as \"start_time: NaiveDateTime\", end_time as \"end_time: NaiveDateTime\"  
+            SELECT id, start_time as \"start_time: NaiveDateTime\", end_time as \"end_time: NaiveDateTime\", note, project  
             FROM work_sessions 
             ORDER BY id desc 
             LIMIT 1
@@ -96,15 +182,33 @@ impl WorktimeDatabase {
                 last.id,
                 last.start_time,
                 last.end_time,
+                last.note,
+                last.project,
             )))),
             Err(sqlx::Error::RowNotFound) => Ok(None),
             Err(e) => Err(e),
         }
     }
 
+    pub async fn get_first_session(&self) -> Result<Option<WorktimeSession>> {
+        let first = sqlx::query!(
+            r#"
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project
+                FROM work_sessions
+                ORDER BY start_time asc
+                LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(first
+            .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project))))
+    }
+
     pub async fn get_last_n_sessions_desc(&self, n: u32) -> Result<Vec<WorktimeSession>> {
         let last = sqlx::query!("
-               SELECT id, start_time as \"start_time: NaiveDateTime\", end_time as \"end_time: NaiveDateTime\"  
+               SELECT id, start_time as \"start_time: NaiveDateTime\", end_time as \"end_time: NaiveDateTime\", note, project  
                FROM work_sessions 
                ORDER BY id desc 
                LIMIT $1
@@ -113,15 +217,56 @@ impl WorktimeDatabase {
         .await;
 
         last.map(|rows| {
-            rows.iter()
-                .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time)))
+            rows.into_iter()
+                .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
+                .collect()
+        })
+    }
+
+    /// newest first
+    pub async fn get_sessions_paged(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<WorktimeSession>> {
+        let page = sqlx::query!(r#"
+               SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project
+               FROM work_sessions
+               ORDER BY id DESC
+               LIMIT $1
+               OFFSET $2
+           "#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        page.map(|rows| {
+            rows.into_iter()
+                .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
                 .collect()
         })
     }
 
-    pub async fn get_nth_last_session(&self, n: u32) -> Result<WorktimeSession> {
+    pub async fn count_sessions(&self) -> Result<u32> {
+        let count = sqlx::query!(
+            r#"
+                SELECT count(*) as count
+                FROM work_sessions
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .count;
+
+        Ok(count as u32)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_nth_last_session(&self, n: u32) -> CommandResult<WorktimeSession> {
         let last = sqlx::query!(r#"
-               SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime"  
+               SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project  
                FROM work_sessions 
                ORDER BY id desc 
                LIMIT 1
@@ -130,35 +275,184 @@ impl WorktimeDatabase {
             n
         )
         .fetch_optional(&self.pool)
-        .await;
+        .await?;
 
-        last.and_then(|r_opt| {
-            match r_opt.map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time))) {
-                Some(worktime) => Ok(worktime),
-                None => Err(sqlx::Error::RowNotFound),
+        match last
+            .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
+        {
+            Some(worktime) => Ok(worktime),
+            None => {
+                let count = self.count_sessions().await?;
+                Err(format!("No session at position {n}; only {count} sessions exist").into())
             }
-        })
+        }
     }
 
+    #[instrument(level = "debug", skip(self))]
     pub async fn get_sessions_since(&self, day: NaiveDate) -> Result<Vec<WorktimeSession>> {
         let r = sqlx::query!(
             r#"
-                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime"  
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project  
                 FROM work_sessions 
                 WHERE date(start_time) >= date($1)
+                   OR end_time IS NULL
+                   OR date(end_time) >= date($1)
                 ORDER BY id asc
             "#,
             day
-        ).fetch_all(&self.pool).await;
+        ).fetch_all(&self.pool).await?;
 
-        r.map(|rows| {
-            rows.iter()
-                .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time)))
-                .collect()
-        })
+        let mut sessions: Vec<WorktimeSession> = r
+            .into_iter()
+            .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
+            .collect();
+        self.attach_breaks(&mut sessions).await?;
+        Ok(sessions)
+    }
+
+    /// sessions of `[from, to]`, including those reaching into it from the day before
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_sessions_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<WorktimeSession>> {
+        let r = sqlx::query!(
+            r#"
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project
+                FROM work_sessions
+                WHERE date(start_time) <= date($2)
+                  AND (date(start_time) >= date($1) OR end_time IS NULL OR date(end_time) >= date($1))
+                ORDER BY id asc
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sessions: Vec<WorktimeSession> = r
+            .into_iter()
+            .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
+            .collect();
+        self.attach_breaks(&mut sessions).await?;
+        Ok(sessions)
+    }
+
+    async fn attach_breaks(&self, sessions: &mut [WorktimeSession]) -> Result<()> {
+        let Some(first) = sessions.iter().map(|s| s.id.0).min() else {
+            return Ok(());
+        };
+        let breaks = sqlx::query!(
+            r#"
+                SELECT session_id, pause_start as "pause_start: NaiveDateTime", pause_end as "pause_end: NaiveDateTime"
+                FROM breaks
+                WHERE session_id >= $1
+                ORDER BY id asc
+            "#,
+            first
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for r in breaks {
+            if let Some(session) = sessions.iter_mut().find(|s| s.id.0 == r.session_id) {
+                session.breaks.push(WorktimeBreak {
+                    start: r.pause_start,
+                    end: r.pause_end,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// the open break of session `id`, i.e. `Some` while it is paused
+    pub async fn get_open_break(&self, id: WorktimeSessionId) -> Result<Option<WorktimeBreak>> {
+        let open = sqlx::query!(
+            r#"
+                SELECT pause_start as "pause_start: NaiveDateTime"
+                FROM breaks
+                WHERE session_id = $1 AND pause_end IS NULL
+            "#,
+            id.0
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(open.map(|r| WorktimeBreak {
+            start: r.pause_start,
+            end: None,
+        }))
+    }
+
+    #[instrument(level = "debug", skip(self, clock))]
+    pub async fn insert_pause(
+        &self,
+        id: WorktimeSessionId,
+        clock: &impl Clock,
+    ) -> Result<NaiveDateTime> {
+        let now = clock.get_now();
+        sqlx::query!(
+            "INSERT INTO breaks (session_id, pause_start) VALUES ($1, $2)",
+            id.0,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(now)
+    }
+
+    #[instrument(level = "debug", skip(self, clock))]
+    pub async fn insert_resume(
+        &self,
+        id: WorktimeSessionId,
+        clock: &impl Clock,
+    ) -> Result<NaiveDateTime> {
+        let now = clock.get_now();
+        sqlx::query!(
+            r#"
+            UPDATE breaks
+            SET pause_end = $1
+            WHERE session_id = $2 AND pause_end IS NULL
+            "#,
+            now,
+            id.0
+        )
+        .execute(&self.pool)
+        .await
+        .and_then(result_from_rows_affected)?;
+        Ok(now)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_sessions_on(&self, day: NaiveDate) -> Result<Vec<WorktimeSession>> {
+        let r = sqlx::query!(
+            r#"
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project
+                FROM work_sessions
+                WHERE date(start_time) = date($1)
+                ORDER BY id asc
+            "#,
+            day
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sessions: Vec<WorktimeSession> = r
+            .into_iter()
+            .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
+            .collect();
+        self.attach_breaks(&mut sessions).await?;
+        Ok(sessions)
     }
 
-    pub async fn insert_start(&self, clock: &impl Clock) -> CommandResult<NaiveDateTime> {
+    #[instrument(level = "debug", skip(self, clock))]
+    pub async fn insert_start(
+        &self,
+        clock: &impl Clock,
+        note: Option<&str>,
+        project: Option<&str>,
+    ) -> CommandResult<NaiveDateTime> {
         let c = sqlx::query!(
             r#"
                 SELECT count(*) as open_sessions
@@ -173,20 +467,147 @@ impl WorktimeDatabase {
         match c {
             0 => (),
             1 => return Err("Session already started".into()),
-            n => panic!("Corrupt data - {n} sessions running!"),
+            n => return Err(format!("Corrupt data - {n} sessions running! Run `repair`").into()),
         }
 
         let now = clock.get_now();
-        sqlx::query!("INSERT INTO work_sessions (start_time) VALUES ($1)", now)
+        sqlx::query!(
+            "INSERT INTO work_sessions (start_time, note, project) VALUES ($1, $2, $3)",
+            now,
+            note,
+            project
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(now)
+    }
+
+    /// replaces an existing holiday on `date`
+    #[instrument(level = "debug", skip(self))]
+    pub async fn insert_holiday(&self, date: NaiveDate, kind: HolidayKind) -> Result<()> {
+        let kind = kind.to_string();
+        sqlx::query!(
+            "INSERT OR REPLACE INTO holidays (date, kind) VALUES ($1, $2)",
+            date,
+            kind
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub async fn delete_holiday(&self, date: NaiveDate) -> Result<()> {
+        sqlx::query!("DELETE FROM holidays WHERE date = $1", date)
+            .execute(&self.pool)
+            .await
+            .and_then(result_from_rows_affected)
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_holidays_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, HolidayKind)>> {
+        let r = sqlx::query!(
+            r#"
+                SELECT date as "date!: NaiveDate", kind
+                FROM holidays
+                WHERE date BETWEEN $1 AND $2
+                ORDER BY date
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(r.into_iter()
+            .filter_map(|r| Some((r.date, r.kind.parse().ok()?)))
+            .collect())
+    }
+
+    /// `VACUUM INTO` - a consistent snapshot even while the db is in use; `path` must not exist
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO $1")
+            .bind(path.to_string_lossy())
             .execute(&self.pool)
             .await?;
-        Ok(now)
+        Ok(())
+    }
+
+    /// records a finished session after the fact
+    #[instrument(level = "debug", skip(self))]
+    pub async fn insert_full_session(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> CommandResult<WorktimeSessionId> {
+        if end < start {
+            return Err("End would be before start".into());
+        }
+        let neighbours = self.get_sessions_between(start.date(), end.date()).await?;
+        if let Some(other) = neighbours.iter().find(|s| s.overlaps(start, end)) {
+            return Err(format!("Overlaps session '{other}'").into());
+        }
+
+        let id = sqlx::query!(
+            "INSERT INTO work_sessions (start_time, end_time) VALUES ($1, $2)",
+            start,
+            end
+        )
+        .execute(&self.pool)
+        .await?
+        .last_insert_rowid();
+        Ok(id.into())
+    }
+
+    /// indices of `sessions` overlapping recorded ones or an earlier entry of `sessions`
+    pub async fn find_overlapping(
+        &self,
+        sessions: &[(NaiveDateTime, NaiveDateTime)],
+    ) -> Result<Vec<usize>> {
+        let (Some(from), Some(to)) = (
+            sessions.iter().map(|(start, _)| start.date()).min(),
+            sessions.iter().map(|(_, end)| end.date()).max(),
+        ) else {
+            return Ok(Vec::new());
+        };
+        let mut known = self.get_sessions_between(from, to).await?;
+        let mut overlapping = Vec::new();
+        for (i, (start, end)) in sessions.iter().enumerate() {
+            if known.iter().any(|s| s.overlaps(*start, *end)) {
+                overlapping.push(i);
+            } else {
+                known.push(WorktimeSession::new(0i64.into(), *start, Some(*end)));
+            }
+        }
+        Ok(overlapping)
+    }
+
+    /// all or nothing - a failing insert rolls the others back
+    #[instrument(level = "debug", skip_all, fields(count = sessions.len()))]
+    pub async fn insert_full_sessions(
+        &self,
+        sessions: &[(NaiveDateTime, NaiveDateTime)],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (start, end) in sessions {
+            sqlx::query!(
+                "INSERT INTO work_sessions (start_time, end_time) VALUES ($1, $2)",
+                start,
+                end
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
     }
 
-    #[allow(dead_code)]
     pub async fn get_session_by_id(&self, id: WorktimeSessionId) -> Result<WorktimeSession> {
         let r = sqlx::query!(r#"
-                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime"  
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project  
                 FROM work_sessions 
                 WHERE id = $1
             "#, 
@@ -195,19 +616,364 @@ impl WorktimeDatabase {
             .fetch_one(&self.pool)
             .await;
 
-        r.map(|row| WorktimeSession::from((row.id, row.start_time, row.end_time)))
+        r.map(|row| {
+            WorktimeSession::from((row.id, row.start_time, row.end_time, row.note, row.project))
+        })
+    }
+
+    /// marks the running session as seen at `now` - unless the last mark is older than `idle`,
+    /// so the time before a sleep/suspend stays visible (see [`crate::time::idle_warning`])
+    pub async fn record_heartbeat(&self, now: NaiveDateTime, idle: TimeDelta) -> Result<()> {
+        let stale = now - idle;
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET last_seen = $1
+            WHERE end_time IS NULL AND (last_seen IS NULL OR last_seen >= $2)
+            "#,
+            now,
+            stale
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// the heartbeat's process exits cleanly: forgets a fresh mark, so a later one-shot command
+    /// doesn't take the time nothing was running for a sleep; a stale one stays for the warning
+    pub async fn end_heartbeat(&self, now: NaiveDateTime, idle: TimeDelta) -> Result<()> {
+        let stale = now - idle;
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET last_seen = NULL
+            WHERE end_time IS NULL AND last_seen >= $1
+            "#,
+            stale
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_last_seen(&self, id: WorktimeSessionId) -> Result<Option<NaiveDateTime>> {
+        sqlx::query_scalar!(
+            r#"SELECT last_seen as "last_seen: NaiveDateTime" FROM work_sessions WHERE id = $1"#,
+            id.0
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// records a heartbeat every minute while the process runs; the first one only after a
+    /// minute, so one-shot commands leave no trace; abort it before [`Self::end_heartbeat`]
+    pub fn spawn_heartbeat(
+        &self,
+        clock: impl Clock + Send + 'static,
+        idle: TimeDelta,
+    ) -> JoinHandle<()> {
+        let db = Self {
+            pool: self.pool.clone(),
+            path: self.path.clone(),
+        };
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let _ = db.record_heartbeat(clock.get_now(), idle).await;
+            }
+        })
     }
 
+    #[instrument(level = "debug", skip(self, clock))]
     pub async fn insert_stop(
         &self,
         id: WorktimeSessionId,
         clock: &impl Clock,
-    ) -> Result<NaiveDateTime> {
-        let now = clock.get_now();
+    ) -> CommandResult<NaiveDateTime> {
+        self.stop_at(id, clock.get_now()).await
+    }
+
+    /// ends session `id` (and a running break) at `now`, e.g. an idle session where it was
+    /// last seen
+    #[instrument(level = "debug", skip(self))]
+    pub async fn stop_at(
+        &self,
+        id: WorktimeSessionId,
+        now: NaiveDateTime,
+    ) -> CommandResult<NaiveDateTime> {
+        if now < self.get_session_by_id(id).await?.start {
+            return Err("End would be before start".into());
+        }
         self.update_end_time(id, &now).await?;
+        // stopping while paused ends the break as well
+        sqlx::query!(
+            r#"
+            UPDATE breaks
+            SET pause_end = $1
+            WHERE session_id = $2 AND pause_end IS NULL
+            "#,
+            now,
+            id.0
+        )
+        .execute(&self.pool)
+        .await?;
         Ok(now)
     }
 
+    /// joins two closed sessions at most `max_gap` apart into the earlier one (keeping its
+    /// note), moving the later one's breaks over - all or nothing
+    #[instrument(level = "debug", skip(self))]
+    pub async fn merge_sessions(
+        &self,
+        a: WorktimeSessionId,
+        b: WorktimeSessionId,
+        max_gap: TimeDelta,
+        preserve_total: bool,
+    ) -> CommandResult<WorktimeSession> {
+        let mut tx = self.pool.begin().await?;
+        let mut sessions = Vec::new();
+        for id in [a, b] {
+            let r = sqlx::query!(
+                r#"
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project
+                FROM work_sessions
+                WHERE id = $1
+                "#,
+                id.0
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            sessions.push(WorktimeSession::from((
+                r.id,
+                r.start_time,
+                r.end_time,
+                r.note,
+                r.project,
+            )));
+        }
+        sessions.sort_by_key(|s| s.start);
+        let [earlier, later] = [&sessions[0], &sessions[1]];
+        let (Some(earlier_end), Some(later_end)) = (earlier.end, later.end) else {
+            return Err("Can't merge a running session".into());
+        };
+        if later.start < earlier_end {
+            return Err(format!("'{}' and '{}' overlap", earlier.id, later.id).into());
+        }
+        let gap = later.start - earlier_end;
+        if gap > max_gap {
+            return Err(format!(
+                "Gap of {}min between '{}' and '{}' exceeds {}min (`WORKTIME_MERGE_GAP_MINUTES`)",
+                gap.num_minutes(),
+                earlier.id,
+                later.id,
+                max_gap.num_minutes()
+            )
+            .into());
+        }
+
+        let start = earlier.start;
+        let end = match preserve_total {
+            true => later_end - gap,
+            false => later_end,
+        };
+        sqlx::query!(
+            "UPDATE breaks SET session_id = $1 WHERE session_id = $2",
+            earlier.id.0,
+            later.id.0
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM work_sessions WHERE id = $1", later.id.0)
+            .execute(&mut *tx)
+            .await
+            .and_then(result_from_rows_affected)?;
+        sqlx::query!(
+            "UPDATE work_sessions SET end_time = $1 WHERE id = $2",
+            end,
+            earlier.id.0
+        )
+        .execute(&mut *tx)
+        .await
+        .and_then(result_from_rows_affected)?;
+
+        tx.commit().await?;
+        Ok(WorktimeSession::new(earlier.id, start, Some(end)))
+    }
+
+    /// ends session `id` at `at` and continues it (note, project, later breaks) in a new
+    /// session from `at` - all or nothing
+    #[instrument(level = "debug", skip(self))]
+    pub async fn split_session(
+        &self,
+        id: WorktimeSessionId,
+        at: NaiveDateTime,
+    ) -> CommandResult<WorktimeSessionId> {
+        let mut tx = self.pool.begin().await?;
+        let spanning = sqlx::query_scalar!(
+            r#"
+            SELECT count(*) FROM breaks
+            WHERE session_id = $1 AND pause_start < $2 AND (pause_end IS NULL OR pause_end > $2)
+            "#,
+            id.0,
+            at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if spanning > 0 {
+            return Err(format!("{} falls into a break", display_time(&at)).into());
+        }
+
+        let second = sqlx::query!(
+            r#"
+            INSERT INTO work_sessions (start_time, end_time, note, project)
+            SELECT $1, end_time, note, project FROM work_sessions WHERE id = $2
+            "#,
+            at,
+            id.0
+        )
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        sqlx::query!(
+            "UPDATE breaks SET session_id = $1 WHERE session_id = $2 AND pause_start >= $3",
+            second,
+            id.0,
+            at
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE work_sessions SET end_time = $1 WHERE id = $2",
+            at,
+            id.0
+        )
+        .execute(&mut *tx)
+        .await
+        .and_then(result_from_rows_affected)?;
+
+        tx.commit().await?;
+        Ok(second.into())
+    }
+
+    /// merges all (closed) sessions starting on `day` into the earliest one;
+    /// returns the merged session and how many sessions went into it
+    #[instrument(level = "debug", skip(self))]
+    pub async fn merge_sessions_on(
+        &self,
+        day: NaiveDate,
+        preserve_total: bool,
+    ) -> CommandResult<(WorktimeSession, usize)> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut sessions: Vec<WorktimeSession> = sqlx::query!(
+            r#"
+                SELECT id, start_time as "start_time: NaiveDateTime", end_time as "end_time: NaiveDateTime", note, project
+                FROM work_sessions
+                WHERE date(start_time) = date($1)
+                ORDER BY start_time asc
+            "#,
+            day
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
+        .collect();
+
+        if sessions.len() < 2 {
+            return Err(format!("Nothing to merge on {day}").into());
+        }
+        if sessions.iter().any(|s| s.is_open()) {
+            return Err("Can't merge a running session".into());
+        }
+        let breaks = sqlx::query!(
+            r#"
+                SELECT session_id, pause_start as "pause_start: NaiveDateTime", pause_end as "pause_end: NaiveDateTime"
+                FROM breaks
+                WHERE session_id IN (SELECT id FROM work_sessions WHERE date(start_time) = date($1))
+                ORDER BY id asc
+            "#,
+            day
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for r in breaks {
+            if let Some(session) = sessions.iter_mut().find(|s| s.id.0 == r.session_id) {
+                session.breaks.push(WorktimeBreak {
+                    start: r.pause_start,
+                    end: r.pause_end,
+                });
+            }
+        }
+
+        let first = &sessions[0];
+        let start = first.start;
+        let end = if preserve_total {
+            // the breaks stay within the merged session, so it has to cover them again
+            let paused: TimeDelta = sessions
+                .iter()
+                .flat_map(|s| &s.breaks)
+                .map(|pause| pause.end.unwrap_or(pause.start) - pause.start)
+                .sum();
+            start + aggregate_session_times(&sessions, start) + paused
+        } else {
+            sessions
+                .iter()
+                .filter_map(|s| s.end)
+                .max()
+                .expect("all sessions are closed")
+        };
+
+        // deleting a session cascades to its breaks, so they move over first
+        for session in &sessions[1..] {
+            sqlx::query!(
+                "UPDATE breaks SET session_id = $1 WHERE session_id = $2",
+                first.id.0,
+                session.id.0
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!("DELETE FROM work_sessions WHERE id = $1", session.id.0)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET start_time = $1, end_time = $2
+            WHERE id = $3
+            "#,
+            start,
+            end,
+            first.id.0
+        )
+        .execute(&mut *tx)
+        .await
+        .and_then(result_from_rows_affected)?;
+
+        tx.commit().await?;
+        Ok((
+            WorktimeSession::new(first.id, start, Some(end)),
+            sessions.len(),
+        ))
+    }
+
+    /// see [`find_inconsistencies`]
+    pub async fn check_integrity(&self) -> Result<Vec<String>> {
+        find_inconsistencies(&self.pool).await
+    }
+
+    /// latest successfully applied migration
+    pub async fn get_schema_version(&self) -> Result<Option<i64>> {
+        // NOTE: not via `query!` - the table is owned by sqlx' migrator
+        sqlx::query_scalar("SELECT max(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    #[instrument(level = "debug", skip(self))]
     pub async fn update_start_time(
         &self,
         id: WorktimeSessionId,
@@ -227,6 +993,133 @@ impl WorktimeDatabase {
         .and_then(result_from_rows_affected)
     }
 
+    /// sets session `id` to `start`-`end` and moves its breaks by `shift` (whole days keep the
+    /// times of day) - all or nothing
+    #[instrument(level = "debug", skip(self))]
+    pub async fn correct_session(
+        &self,
+        id: WorktimeSessionId,
+        shift: TimeDelta,
+        start: NaiveDateTime,
+        end: Option<NaiveDateTime>,
+    ) -> Result<()> {
+        let modifier = format!("{:+} seconds", shift.num_seconds());
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET start_time = $1, end_time = $2
+            WHERE id = $3
+            "#,
+            start,
+            end,
+            id.0
+        )
+        .execute(&mut *tx)
+        .await
+        .and_then(result_from_rows_affected)?;
+        sqlx::query!(
+            r#"
+            UPDATE breaks
+            SET pause_start = datetime(pause_start, $1), pause_end = datetime(pause_end, $1)
+            WHERE session_id = $2
+            "#,
+            modifier,
+            id.0
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    pub async fn delete_session(&self, id: WorktimeSessionId) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM work_sessions
+            WHERE id = $1
+            "#,
+            id.0
+        )
+        .execute(&self.pool)
+        .await
+        .and_then(result_from_rows_affected)
+    }
+
+    /// clears the end of session `id` again, including a break closed by that stop
+    pub async fn reopen_session(&self, id: WorktimeSessionId, end: &NaiveDateTime) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            UPDATE breaks
+            SET pause_end = NULL
+            WHERE session_id = $1 AND pause_end = $2
+            "#,
+            id.0,
+            end
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET end_time = NULL
+            WHERE id = $1
+            "#,
+            id.0
+        )
+        .execute(&mut *tx)
+        .await
+        .and_then(result_from_rows_affected)?;
+        tx.commit().await
+    }
+
+    pub async fn get_distinct_projects(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query!(
+            r#"
+                SELECT DISTINCT project as "project!"
+                FROM work_sessions
+                WHERE project IS NOT NULL
+                ORDER BY project asc
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| r.project).collect())
+    }
+
+    /// closes every open session but the latest at its own start; returns how many were closed
+    pub async fn close_stale_open_sessions(&self) -> Result<u64> {
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET end_time = start_time
+            WHERE end_time IS NULL
+              AND id != (SELECT max(id) FROM work_sessions WHERE end_time IS NULL)
+            "#
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+
+    pub async fn update_note(&self, id: WorktimeSessionId, note: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE work_sessions
+            SET note = $1
+            WHERE id = $2
+            "#,
+            note,
+            id.0
+        )
+        .execute(&self.pool)
+        .await
+        .and_then(result_from_rows_affected)
+    }
+
+    #[instrument(level = "debug", skip(self))]
     pub async fn update_end_time(
         &self,
         id: WorktimeSessionId,
@@ -264,7 +1157,19 @@ fn result_from_rows_affected(
 // ####################
 // CHECKS
 // ####################
-async fn sanity_check(pool: SqlitePool) -> Result<()> {
+/// warns on stderr about every problem found (instead of refusing to run)
+async fn sanity_check(pool: SqlitePool) -> Result<Vec<String>> {
+    let problems = find_inconsistencies(&pool).await?;
+    for problem in &problems {
+        eprintln!("Warning: {problem}");
+    }
+    Ok(problems)
+}
+
+/// multiple running sessions, sessions ending before they start & overlapping sessions
+async fn find_inconsistencies(pool: &SqlitePool) -> Result<Vec<String>> {
+    let mut problems = Vec::new();
+
     let open_sessions = sqlx::query!(
         "
         SELECT count(*) as open_sessions
@@ -272,56 +1177,56 @@ async fn sanity_check(pool: SqlitePool) -> Result<()> {
         WHERE end_time IS NULL
         "
     )
-    .fetch_one(&pool)
+    .fetch_one(pool)
     .await?
     .open_sessions;
 
-    match open_sessions {
-        0 | 1 => (),
-        n => panic!("Corrupt data - {n} sessions running!"),
+    if open_sessions > 1 {
+        problems.push(format!("Corrupt data - {open_sessions} sessions running!"));
     }
 
     let mut all_sessions: Vec<WorktimeSession> = sqlx::query!("
-        SELECT id, start_time as \"start_time: NaiveDateTime\", end_time as \"end_time: NaiveDateTime\"  
+        SELECT id, start_time as \"start_time: NaiveDateTime\", end_time as \"end_time: NaiveDateTime\", note, project  
         FROM work_sessions 
     ")
-        .fetch_all(&pool)
+        .fetch_all(pool)
         .await?
-        .iter()
-        .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time)))
+        .into_iter()
+        .map(|r| WorktimeSession::from((r.id, r.start_time, r.end_time, r.note, r.project)))
         .collect();
 
     if !all_sessions.is_sorted_by_key(|s| s.start) {
         all_sessions.sort_by_key(|s| s.start);
     };
 
-    all_sessions.into_iter().fold(
-        None,
-        |last_end, WorktimeSession { id, start, end }| {
-            if let Some(end) = end {
-                assert!(
-                    end >= start,
-                    "Corrupt data - Session '{id}' end {end:?} before start {start:?}"
-                );
-            }
-            if let Some(last_end) = last_end{
-                assert!(
-                    start >= last_end,
-                    "Corrupt data - Session '{id}' overlap prev. end {last_end:?} after next start {start:?}"
-                );
-            }
-
-            end
-        },
-    );
+    let mut last_end = None;
+    for WorktimeSession { id, start, end, .. } in all_sessions {
+        if let Some(end) = end
+            && end < start
+        {
+            problems.push(format!(
+                "Corrupt data - Session '{id}' end {end:?} before start {start:?}"
+            ));
+        }
+        if let Some(last_end) = last_end
+            && start < last_end
+        {
+            problems.push(format!(
+                "Corrupt data - Session '{id}' overlap prev. end {last_end:?} after next start {start:?}"
+            ));
+        }
+        last_end = end;
+    }
 
-    Ok(())
+    Ok(problems)
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub async fn get_test_worktime_db() -> Result<WorktimeDatabase> {
     use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 
+    // journal mode and busy timeout of `crate::connect` don't apply: a single in-memory
+    // connection has neither a file to log to nor another writer to wait for
     let opts = SqliteConnectOptions::new().in_memory(true);
     let pool = SqlitePoolOptions::new()
         // NOTE:
@@ -349,7 +1254,7 @@ mod tests {
         let db1 = get_test_worktime_db().await?;
         let db2 = get_test_worktime_db().await?;
 
-        db1.insert_start(&clock).await.unwrap();
+        db1.insert_start(&clock, None, None).await.unwrap();
         let last_1 = db1.get_last_session().await?;
         let last_2 = db2.get_last_session().await?;
 
@@ -358,31 +1263,115 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn heartbeat_should_freeze_after_idle_gap() -> Result<()> {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await?;
+        let idle = TimeDelta::minutes(15);
+        clock.set(1, 9, 0);
+        db.insert_start(&clock, None, None).await.unwrap();
+        let id = db.get_last_session().await?.unwrap().id;
+
+        db.record_heartbeat(clock.get(1, 9, 1), idle).await?;
+        db.record_heartbeat(clock.get(1, 9, 2), idle).await?;
+        assert_eq!(db.get_last_seen(id).await?, Some(clock.get(1, 9, 2)));
+
+        // woke up after a sleep - the last mark before it stays
+        db.record_heartbeat(clock.get(1, 11, 0), idle).await?;
+        assert_eq!(db.get_last_seen(id).await?, Some(clock.get(1, 9, 2)));
+
+        // quitting after the sleep keeps it visible for the next `status`/`stop`
+        db.end_heartbeat(clock.get(1, 11, 1), idle).await?;
+        assert_eq!(db.get_last_seen(id).await?, Some(clock.get(1, 9, 2)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn heartbeat_should_forget_a_clean_quit() -> Result<()> {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await?;
+        let idle = TimeDelta::minutes(15);
+        clock.set(1, 9, 0);
+        db.insert_start(&clock, None, None).await.unwrap();
+        let id = db.get_last_session().await?.unwrap().id;
+
+        // interactive mode left open for a minute, then quit
+        db.record_heartbeat(clock.get(1, 9, 1), idle).await?;
+        db.end_heartbeat(clock.get(1, 9, 2), idle).await?;
+        // a one-shot `stop` in the evening finds nothing to warn about
+        assert_eq!(db.get_last_seen(id).await?, None);
+
+        // and the next long-running process marks it again
+        db.record_heartbeat(clock.get(1, 14, 0), idle).await?;
+        assert_eq!(db.get_last_seen(id).await?, Some(clock.get(1, 14, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn duration_should_run_open_sessions_until_now_minus_breaks() {
+        let clock = MockClock::default();
+        let closed =
+            WorktimeSession::new(1i64.into(), clock.get(1, 9, 0), Some(clock.get(1, 17, 0)));
+        let open = WorktimeSession {
+            breaks: vec![WorktimeBreak {
+                start: clock.get(2, 12, 0),
+                end: None,
+            }],
+            ..WorktimeSession::new(2i64.into(), clock.get(2, 9, 0), None)
+        };
+        let now = clock.get(2, 13, 0);
+
+        assert!(!closed.is_open());
+        assert_eq!(closed.duration(now), TimeDelta::hours(8));
+        assert!(open.is_open());
+        assert_eq!(open.duration(now), TimeDelta::hours(3));
+    }
+
+    #[tokio::test]
+    async fn pages_should_line_up_with_nth_last() -> Result<()> {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await?;
+        for day in 1..=12 {
+            db.insert_full_session(clock.get(day, 9, 0), clock.get(day, 17, 0))
+                .await
+                .unwrap();
+        }
+
+        let second_page = db.get_sessions_paged(10, 10).await?;
+
+        assert_eq!(second_page.len(), 2);
+        for (i, session) in second_page.iter().enumerate() {
+            let nth_last = db.get_nth_last_session(10 + i as u32).await.unwrap();
+            assert_eq!(session.id, nth_last.id);
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_last() -> sqlx::Result<()> {
         let clock = MockClock::default();
         let db = get_test_worktime_db().await?;
 
         clock.set(4, 8, 0);
-        db.insert_start(&clock).await.unwrap();
+        db.insert_start(&clock, None, None).await.unwrap();
         clock.set(4, 12, 0);
         let id_1 = db.get_last_session().await.unwrap().unwrap().id;
-        db.insert_stop(id_1, &clock).await?;
+        db.insert_stop(id_1, &clock).await.unwrap();
 
         clock.set(4, 13, 0);
-        db.insert_start(&clock).await.unwrap();
+        db.insert_start(&clock, None, None).await.unwrap();
         clock.set(4, 17, 0);
         let id_2 = db.get_last_session().await.unwrap().unwrap().id;
-        db.insert_stop(id_2, &clock).await?;
+        db.insert_stop(id_2, &clock).await.unwrap();
 
         clock.set(5, 8, 30);
-        db.insert_start(&clock).await.unwrap();
+        db.insert_start(&clock, None, None).await.unwrap();
         clock.set(5, 12, 0);
         let id_3 = db.get_last_session().await.unwrap().unwrap().id;
-        db.insert_stop(id_3, &clock).await?;
+        db.insert_stop(id_3, &clock).await.unwrap();
 
-        assert_eq!(id_3, db.get_nth_last_session(0).await?.id);
-        assert_eq!(id_2, db.get_nth_last_session(1).await?.id);
+        assert_eq!(id_3, db.get_nth_last_session(0).await.unwrap().id);
+        assert_eq!(id_2, db.get_nth_last_session(1).await.unwrap().id);
         assert_eq!(
             vec![id_3, id_2],
             db.get_last_n_sessions_desc(2)
@@ -394,4 +1383,70 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_first() -> Result<()> {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await?;
+        assert_eq!(None, db.get_first_session().await?);
+
+        for d in [3, 4, 5] {
+            clock.set(d, 9, 0);
+            db.insert_start(&clock, None, None).await.unwrap();
+            clock.set(d, 17, 0);
+            let id = db.get_last_session().await?.unwrap().id;
+            db.insert_stop(id, &clock).await.unwrap();
+        }
+        // corrected into the past - id order != time order
+        let last = db.get_last_session().await?.unwrap();
+        db.update_start_time(last.id, &clock.get(2, 9, 0)).await?;
+
+        let first = db.get_first_session().await?.unwrap();
+        assert_eq!(last.id, first.id);
+        assert_eq!(clock.get(2, 9, 0), first.start);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_large_ids() -> Result<()> {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await?;
+        let large_id = i64::from(u32::MAX) + 1;
+        let now = clock.get_now();
+
+        sqlx::query!(
+            "INSERT INTO work_sessions (id, start_time) VALUES ($1, $2)",
+            large_id,
+            now
+        )
+        .execute(&db.pool)
+        .await?;
+
+        let last = db.get_last_session().await?.unwrap();
+        assert_eq!(WorktimeSessionId::from(large_id), last.id);
+        assert_eq!(large_id.to_string(), last.id.to_string());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_count_sessions() -> Result<()> {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await?;
+        assert_eq!(0, db.count_sessions().await?);
+
+        for d in 1..=3 {
+            clock.set(d, 9, 0);
+            db.insert_start(&clock, None, None).await.unwrap();
+            clock.set(d, 17, 0);
+            let id = db.get_last_session().await?.unwrap().id;
+            db.insert_stop(id, &clock).await.unwrap();
+        }
+
+        assert_eq!(3, db.count_sessions().await?);
+        assert_eq!(
+            Err("No session at position 3; only 3 sessions exist".into()),
+            db.get_nth_last_session(3).await
+        );
+        Ok(())
+    }
 }
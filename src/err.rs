@@ -1,6 +1,40 @@
+use crate::stdout::TableRow;
 use std::{fmt, sync::Arc};
 
 pub type CommandResult<T = String> = std::result::Result<T, CommandError>;
+
+/// the printed message plus structured fields for `--format json`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandOutput {
+    pub message: String,
+    /// (key, json encoded value)
+    pub fields: Vec<(&'static str, String)>,
+    /// printed below the message in text format (empty = no table)
+    pub table: Vec<TableRow>,
+}
+
+impl CommandOutput {
+    pub fn with(mut self, key: &'static str, json_value: impl fmt::Display) -> Self {
+        self.fields.push((key, json_value.to_string()));
+        self
+    }
+
+    pub fn with_table(mut self, table: Vec<TableRow>) -> Self {
+        self.table = table;
+        self
+    }
+}
+
+impl From<String> for CommandOutput {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            fields: Vec::new(),
+            table: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum CommandError {
     DatabaseError(Arc<sqlx::Error>), // ← now Clone
@@ -0,0 +1,108 @@
+use chrono::TimeDelta;
+use strum::Display;
+
+use crate::time::to_hours;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Display)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+    Prometheus,
+}
+
+/// the figures a report is rendered from
+pub struct ReportData {
+    /// `day`, `week`, `month` or `lifetime`
+    pub period: String,
+    pub tracked: TimeDelta,
+    pub sessions: usize,
+}
+
+/// `table` renders the (option dependent) text report
+pub fn render(format: OutputFormat, data: &ReportData, table: impl FnOnce() -> String) -> String {
+    match format {
+        OutputFormat::Table => table(),
+        OutputFormat::Json => json(data),
+        OutputFormat::Csv => csv(data),
+        OutputFormat::Markdown => markdown(data),
+        OutputFormat::Prometheus => prometheus(data),
+    }
+}
+
+/// e.g. `{"period":"week","hours":40.00,"sessions":5}`
+fn json(data: &ReportData) -> String {
+    format!(
+        r#"{{"period":"{}","hours":{:.2},"sessions":{}}}"#,
+        data.period,
+        to_hours(data.tracked),
+        data.sessions
+    )
+}
+
+fn csv(data: &ReportData) -> String {
+    format!(
+        "period,hours,sessions\n{},{:.2},{}",
+        data.period,
+        to_hours(data.tracked),
+        data.sessions
+    )
+}
+
+fn markdown(data: &ReportData) -> String {
+    format!(
+        "| period | hours | sessions |\n| --- | ---: | ---: |\n| {} | {:.2} | {} |",
+        data.period,
+        to_hours(data.tracked),
+        data.sessions
+    )
+}
+
+/// prometheus text exposition, e.g. `worktime_tracked_seconds{period="week"} 144000`
+fn prometheus(data: &ReportData) -> String {
+    format!(
+        "# HELP worktime_tracked_seconds Tracked work time of the period.
+# TYPE worktime_tracked_seconds gauge
+worktime_tracked_seconds{{period=\"{}\"}} {}",
+        data.period,
+        data.tracked.num_seconds()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> ReportData {
+        ReportData {
+            period: "week".to_string(),
+            tracked: TimeDelta::minutes(90),
+            sessions: 2,
+        }
+    }
+
+    #[test]
+    fn should_render_each_format() {
+        let render = |format| render(format, &data(), || "table".to_string());
+
+        assert_eq!(
+            render(OutputFormat::Json),
+            r#"{"period":"week","hours":1.50,"sessions":2}"#
+        );
+        assert_eq!(
+            render(OutputFormat::Csv),
+            "period,hours,sessions\nweek,1.50,2"
+        );
+        assert_eq!(
+            render(OutputFormat::Markdown),
+            "| period | hours | sessions |\n| --- | ---: | ---: |\n| week | 1.50 | 2 |"
+        );
+        assert_eq!(
+            render(OutputFormat::Prometheus).lines().last().unwrap(),
+            r#"worktime_tracked_seconds{period="week"} 5400"#
+        );
+        assert_eq!(render(OutputFormat::Table), "table");
+    }
+}
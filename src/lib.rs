@@ -0,0 +1,245 @@
+//! worktime as a library, e.g. for a GUI or status-bar widget: open a [`WorktimeDatabase`] with
+//! [`connect`] and run any [`WorktimeCommand`](cli::WorktimeCommand) via `execute`
+
+use chrono::TimeDelta;
+use cli::WorktimeCommand;
+use config::CONFIG;
+use db::WorktimeDatabase;
+use directories::ProjectDirs;
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool},
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+use stdin::StdIn;
+use stdout::{ResultFormat, StdOut};
+use time::{Clock, stale_session_warning};
+
+pub mod cli;
+pub mod config;
+pub mod db;
+pub mod err;
+pub mod format;
+pub mod stdin;
+pub mod stdout;
+pub mod time;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+static ONBOARDING: &str = "Welcome to worktime, bruv! Looks like your first time here:
+  - start:  start tracking time
+  - stop:   stop tracking time
+  - report: see your total for the day/week/month";
+
+/// `--db`, else `WORKTIME_DB`, else a db next to the executable (where it used to live),
+/// else the platform's data dir (e.g. `~/.local/share/worktime/worktime.db`)
+pub fn resolve_db_path(flag: Option<PathBuf>, env_var: Option<PathBuf>) -> PathBuf {
+    if let Some(path) = flag.or(env_var) {
+        return path;
+    }
+    let next_to_exe = env::current_exe()
+        .expect("can't find exe path")
+        .join("../worktime.db");
+    if next_to_exe.exists() {
+        return next_to_exe;
+    }
+    ProjectDirs::from("", "", "worktime")
+        .map(|dirs| dirs.data_dir().join("worktime.db"))
+        .unwrap_or(next_to_exe)
+}
+
+/// opens the database at `path` (creating it and its directory if missing) and migrates it;
+/// journal mode and busy timeout follow [`CONFIG`] so several terminals can share the file
+pub async fn connect(path: &Path) -> sqlx::Result<WorktimeDatabase> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let journal_mode = match CONFIG.wal {
+        true => SqliteJournalMode::Wal,
+        false => SqliteJournalMode::Delete,
+    };
+    let opts = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(journal_mode)
+        .busy_timeout(Duration::from_millis(CONFIG.busy_timeout_ms.into()));
+    let pool = SqlitePool::connect_with(opts).await?;
+    MIGRATOR.run(&pool).await?;
+    Ok(WorktimeDatabase::new(pool).with_path(path))
+}
+
+/// guidance for the very first launch (no sessions yet)
+pub async fn onboarding_note(db: &WorktimeDatabase) -> sqlx::Result<Option<&'static str>> {
+    Ok((db.count_sessions().await? == 0).then_some(ONBOARDING))
+}
+
+/// an open session older than [`config::Config::stale_after_hours`] (never closes it)
+pub async fn stale_session_note(
+    db: &WorktimeDatabase,
+    clock: &impl Clock,
+) -> sqlx::Result<Option<String>> {
+    let threshold = TimeDelta::hours(CONFIG.stale_after_hours.into());
+    Ok(db
+        .get_last_session()
+        .await?
+        .and_then(|last| stale_session_warning(&last, clock.get_now(), threshold)))
+}
+
+pub async fn run_loop(
+    clock: &impl Clock,
+    db: &WorktimeDatabase,
+    std_in: &impl StdIn,
+    std_out: &mut impl StdOut,
+) -> ExitCode {
+    let parsed = std_in.parse();
+    // only the command given as args sets the exit code, the interactive ones keep it
+    let mut one_shot = parsed.is_some();
+    let mut exit_code = ExitCode::SUCCESS;
+    let (mut command, format, no_color, dry_run) = match parsed {
+        Some(cli) => (cli.command, cli.format, cli.no_color, cli.dry_run),
+        None => (
+            WorktimeCommand::Status {
+                json: false,
+                watch: false,
+                detailed: false,
+            },
+            ResultFormat::default(),
+            false,
+            false,
+        ),
+    };
+    while !matches!(command, WorktimeCommand::Quit) {
+        tracing::debug!(?command, dry_run, "executing");
+        // a dry run discards nothing, so there's nothing to confirm
+        let confirmation = match dry_run {
+            true => None,
+            false => command.confirmation(db).await,
+        };
+        if !dry_run
+            && let Some(prompt) = command.idle_trim_prompt(db, clock).await
+            && std_in.confirm(&prompt).await
+            && let WorktimeCommand::Stop { trim_idle, .. } = &mut command
+        {
+            *trim_idle = true;
+        }
+        let result = match confirmation {
+            Some(prompt) if !std_in.confirm(&prompt).await => Err("Aborted".into()),
+            _ => command.execute(db, clock, dry_run).await,
+        };
+        let next_page = match command {
+            WorktimeCommand::List {
+                limit,
+                offset,
+                tz_shift,
+            } if result
+                .as_ref()
+                .is_ok_and(|output| output.message.lines().count() == limit as usize) =>
+            {
+                Some(WorktimeCommand::List {
+                    limit,
+                    offset: offset + limit,
+                    tz_shift,
+                })
+            }
+            _ => None,
+        };
+        let table = match &result {
+            Ok(output) if format == ResultFormat::Text => output.table.clone(),
+            _ => Vec::new(),
+        };
+        if std::mem::take(&mut one_shot) && result.is_err() {
+            exit_code = ExitCode::FAILURE;
+        }
+        std_out.print(command, result, format);
+        if !table.is_empty() {
+            std_out.print_table(&table, !no_color);
+        }
+        // plain is for scripts (e.g. a status bar) - one command, no menu afterwards
+        if format == ResultFormat::Plain {
+            return exit_code;
+        }
+        command = match next_page {
+            Some(list) if std_in.prompt_more().await => list,
+            _ => std_in.prompt(db).await,
+        };
+    }
+    exit_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::get_test_worktime_db, time::test_utils::MockClock};
+
+    #[test]
+    fn should_prefer_db_flag_over_env_var() {
+        let flag = PathBuf::from("/tmp/flag.db");
+        let env_var = PathBuf::from("/tmp/env.db");
+
+        assert_eq!(
+            resolve_db_path(Some(flag.clone()), Some(env_var.clone())),
+            flag
+        );
+        assert_eq!(resolve_db_path(None, Some(env_var.clone())), env_var);
+        assert!(resolve_db_path(None, None).ends_with("worktime.db"));
+    }
+
+    #[tokio::test]
+    async fn should_onboard_only_without_sessions() {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await.unwrap();
+
+        assert_eq!(Some(ONBOARDING), onboarding_note(&db).await.unwrap());
+
+        db.insert_start(&clock, None, None).await.unwrap();
+
+        assert_eq!(None, onboarding_note(&db).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_note_stale_session_only_when_long_running() {
+        let clock = MockClock::default();
+        let db = get_test_worktime_db().await.unwrap();
+
+        clock.set(1, 9, 0);
+        db.insert_start(&clock, None, None).await.unwrap();
+        clock.set(1, 18, 0);
+        let fresh = stale_session_note(&db, &clock).await.unwrap();
+        clock.set(3, 9, 0);
+        let stale = stale_session_note(&db, &clock).await.unwrap();
+
+        assert_eq!(fresh, None);
+        assert!(
+            stale
+                .unwrap()
+                .contains("running since 2025-07-01 09:00 (48.00h)")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_connect_migrated_in_wal_mode() {
+        let clock = MockClock::default();
+        let path = env::temp_dir().join(format!("worktime-wal-{}.db", std::process::id()));
+        let wal = path.with_extension("db-wal");
+        let cleanup = || {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+            }
+        };
+        cleanup();
+
+        let db = connect(&path).await.unwrap();
+        assert_eq!(db.path(), Some(path.as_path()));
+        db.insert_start(&clock, None, None).await.unwrap();
+        let sessions = db.count_sessions().await.unwrap();
+        let logged = wal.exists();
+        cleanup();
+
+        assert_eq!(sessions, 1);
+        assert!(logged, "writes should go through {}", wal.display());
+    }
+}
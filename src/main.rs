@@ -1,169 +1,52 @@
-use cli::WorktimeCommand;
-use db::WorktimeDatabase;
-use sqlx::{
-    migrate::Migrator,
-    sqlite::{SqliteConnectOptions, SqlitePool},
+use chrono::TimeDelta;
+use clap::Parser;
+use std::{env, io::IsTerminal, path::PathBuf, process::ExitCode};
+use worktime::{
+    cli::Cli,
+    config::CONFIG,
+    connect, onboarding_note, resolve_db_path, run_loop, stale_session_note,
+    stdin::get_std_in,
+    stdout::get_std_out,
+    time::{Clock, get_clock},
 };
-use std::{env, ops::Deref, path::PathBuf, sync::LazyLock};
-use stdin::{StdIn, get_std_in};
-use stdout::{StdOut, get_std_out};
-use time::{Clock, get_clock};
-
-mod cli;
-mod db;
-mod err;
-mod stdin;
-mod stdout;
-mod time;
-
-static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
-static DB_FILE_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
-    env::current_exe()
-        .expect("can't find exe path")
-        .join("../worktime.db")
-});
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let opts = SqliteConnectOptions::new()
-        .filename(DB_FILE_PATH.deref())
-        .create_if_missing(true);
-    let pool = SqlitePool::connect_with(opts).await?;
-    MIGRATOR.run(&pool).await?;
+async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    // the loop parses (and reports errors) again; this only picks up the global flags
+    let cli = Cli::try_parse().and_then(Cli::validated).ok();
+    // set up first to also log the connection and migrations
+    if cli.as_ref().is_some_and(|cli| cli.verbose) {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(std::io::stderr)
+            .with_ansi(std::io::stderr().is_terminal())
+            .init();
+    }
+    let db_path = resolve_db_path(
+        cli.as_ref().and_then(|cli| cli.db.clone()),
+        env::var_os("WORKTIME_DB").map(PathBuf::from),
+    );
+    let db = connect(&db_path).await?;
 
     let clock = get_clock();
-    let db = WorktimeDatabase::new(pool);
+    let idle = TimeDelta::minutes(CONFIG.idle_after_minutes.into());
+    let heartbeat = (CONFIG.idle_after_minutes > 0).then(|| db.spawn_heartbeat(get_clock(), idle));
     let std_in = get_std_in();
-    let mut std_out = get_std_out();
-    run_loop(&clock, &db, &std_in, &mut std_out).await;
-    Ok(())
-}
-
-async fn run_loop(
-    clock: &impl Clock,
-    db: &WorktimeDatabase,
-    std_in: &impl StdIn,
-    std_out: &mut impl StdOut,
-) {
-    let mut command = std_in.parse().unwrap_or(WorktimeCommand::Status);
-    while !matches!(command, WorktimeCommand::Quit) {
-        let result = command.execute(db, clock).await;
-        std_out.print(command, result);
-        command = std_in.prompt(db).await;
+    let (output_file, quiet) = cli
+        .map(|cli| (cli.output_file, cli.quiet))
+        .unwrap_or_default();
+    let mut std_out = get_std_out(output_file.as_deref(), quiet)?;
+    // stderr, so `worktime completions zsh > _worktime` stays a clean script on a fresh db
+    if let Some(note) = onboarding_note(&db).await? {
+        eprintln!("{note}\n");
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        cli::ReportKind,
-        db::{WorktimeSession, get_test_worktime_db},
-        stdin::test_utils::MockStdIn,
-        stdout::test_utils::StdOutRecorder,
-        time::test_utils::MockClock,
-    };
-
-    async fn setup() -> (MockClock, StdOutRecorder, WorktimeDatabase) {
-        let clock = MockClock::default();
-        let db = get_test_worktime_db().await.unwrap();
-        (clock, StdOutRecorder::default(), db)
+    if let Some(warning) = stale_session_note(&db, &clock).await? {
+        eprintln!("{warning}\n");
     }
-
-    #[tokio::test]
-    async fn should_record_workday() {
-        let (clock, mut recorder, db) = setup().await;
-
-        let std_in: MockStdIn = vec![WorktimeCommand::Start].into();
-        clock.set(1, 9, 00);
-        run_loop(&clock, &db, &std_in, &mut recorder).await;
-
-        let std_in: MockStdIn = vec![
-            WorktimeCommand::Stop,
-            WorktimeCommand::Report {
-                kind: ReportKind::Day,
-            },
-        ]
-        .into();
-        clock.set(1, 15, 00);
-        run_loop(&clock, &db, &std_in, &mut recorder).await;
-
-        let last_out = recorder.results.last().unwrap().clone().unwrap();
-
-        assert_ends_with(last_out.as_str(), "6.00h");
-    }
-
-    fn assert_ends_with(actual: &str, expected_end: &str) {
-        assert!(
-            actual.ends_with(expected_end),
-            "expected '{actual}' to end with '{expected_end}'"
-        );
-    }
-
-    #[tokio::test]
-    async fn should_record_workweek() {
-        let (clock, mut recorder, db) = setup().await;
-
-        for day_offset in 0..5 {
-            clock.set(7 + day_offset, 9, 00); // 7 = Monday
-            let std_in: MockStdIn = vec![WorktimeCommand::Start].into();
-            run_loop(&clock, &db, &std_in, &mut recorder).await;
-
-            let std_in: MockStdIn = vec![WorktimeCommand::Stop].into();
-            clock.set(7 + day_offset, 17, 00); // 7 = Monday
-            run_loop(&clock, &db, &std_in, &mut recorder).await;
-        }
-
-        let std_in: MockStdIn = vec![WorktimeCommand::Report {
-            kind: ReportKind::Week,
-        }]
-        .into();
-        run_loop(&clock, &db, &std_in, &mut recorder).await;
-        let last_out = recorder.results.last().unwrap().clone().unwrap();
-
-        assert_ends_with(last_out.as_str(), "40.00h");
-    }
-
-    #[tokio::test]
-    async fn should_correct() {
-        let (clock, mut recorder, db) = setup().await;
-
-        for day_offset in 0..5 {
-            clock.set(7 + day_offset, 9, 00); // 7 = Monday
-            let std_in: MockStdIn = vec![WorktimeCommand::Start].into();
-            run_loop(&clock, &db, &std_in, &mut recorder).await;
-
-            let std_in: MockStdIn = vec![WorktimeCommand::Stop].into();
-            clock.set(7 + day_offset, 17, 00); // 7 = Monday
-            run_loop(&clock, &db, &std_in, &mut recorder).await;
-        }
-
-        let id_of_wednesday = db.get_nth_last_session(2).await.unwrap().id;
-
-        let std_in: MockStdIn = vec![
-            WorktimeCommand::Correct {
-                nth_last: 2,
-                kind: cli::CorrectionKind::Start,
-                hours: 7,
-                minutes: 30,
-            },
-            WorktimeCommand::Correct {
-                nth_last: 2,
-                kind: cli::CorrectionKind::End,
-                hours: 15,
-                minutes: 30,
-            },
-        ]
-        .into();
-        run_loop(&clock, &db, &std_in, &mut recorder).await;
-
-        let wednesday_corrected = db.get_session_by_id(id_of_wednesday).await.unwrap();
-        let expected = WorktimeSession::new(
-            id_of_wednesday,
-            clock.get(9, 7, 30),
-            Some(clock.get(9, 15, 30)),
-        );
-
-        assert_eq!(expected, wednesday_corrected);
+    let exit_code = run_loop(&clock, &db, &std_in, &mut std_out).await;
+    if let Some(heartbeat) = heartbeat {
+        heartbeat.abort();
+        db.end_heartbeat(clock.get_now(), idle).await?;
     }
+    Ok(exit_code)
 }
@@ -1,18 +1,24 @@
 use crate::{
-    cli::{Cli, CorrectionKind, MainMenuCommand, ReportKind, WorktimeCommand},
-    db::WorktimeDatabase,
+    cli::{Cli, CorrectionKind, MainMenuCommand, ReportKind, ReportOptions, WorktimeCommand},
+    db::{WorktimeDatabase, WorktimeSession},
 };
-use chrono::Timelike;
+use chrono::{NaiveDate, NaiveTime, Timelike};
 use clap::Parser;
-use dialoguer::{Input, Select, theme::ColorfulTheme};
+use dialoguer::{Confirm, Input, Select, theme::ColorfulTheme};
 use std::{env, sync::LazyLock};
 
 /// proxy for all stdin interaction for testability
+// only driven by `run_loop` on the current thread, so no `Send` bound is needed
+#[allow(async_fn_in_trait)]
 pub trait StdIn {
-    fn parse(&self) -> Option<WorktimeCommand>;
+    fn parse(&self) -> Option<Cli>;
     async fn prompt(&self, db: &WorktimeDatabase) -> WorktimeCommand;
-    async fn prompt_report(&self) -> WorktimeCommand;
+    async fn prompt_report(&self, db: &WorktimeDatabase) -> WorktimeCommand;
     async fn prompt_correct(&self, db: &WorktimeDatabase) -> WorktimeCommand;
+    async fn prompt_delete(&self, db: &WorktimeDatabase) -> WorktimeCommand;
+    async fn prompt_add(&self) -> WorktimeCommand;
+    async fn prompt_more(&self) -> bool;
+    async fn confirm(&self, prompt: &str) -> bool;
 }
 
 struct RealStdIn {}
@@ -22,9 +28,9 @@ pub fn get_std_in() -> impl StdIn {
 }
 
 impl StdIn for RealStdIn {
-    fn parse(&self) -> Option<WorktimeCommand> {
-        match Cli::try_parse() {
-            Ok(c) => Some(c.command),
+    fn parse(&self) -> Option<Cli> {
+        match Cli::try_parse().and_then(Cli::validated) {
+            Ok(c) => Some(c),
             Err(e) => {
                 if env::args_os().count() > 1 {
                     let _ = e.print();
@@ -41,32 +47,83 @@ impl StdIn for RealStdIn {
         );
 
         match selection {
-            MainMenuCommand::Status => WorktimeCommand::Status,
-            MainMenuCommand::Start => WorktimeCommand::Start,
-            MainMenuCommand::Stop => WorktimeCommand::Stop,
-            MainMenuCommand::Report => self.prompt_report().await,
+            MainMenuCommand::Status => WorktimeCommand::Status {
+                json: false,
+                watch: false,
+                detailed: false,
+            },
+            MainMenuCommand::Start => WorktimeCommand::Start {
+                note: None,
+                project: None,
+            },
+            MainMenuCommand::Stop => WorktimeCommand::Stop {
+                threshold_alert: None,
+                trim_idle: false,
+            },
+            MainMenuCommand::Cancel => WorktimeCommand::Cancel { yes: false },
+            MainMenuCommand::Undo => WorktimeCommand::Undo,
+            MainMenuCommand::Pause => WorktimeCommand::Pause,
+            MainMenuCommand::Resume => WorktimeCommand::Resume,
+            MainMenuCommand::Continue => WorktimeCommand::Continue,
+            MainMenuCommand::Report => self.prompt_report(db).await,
+            MainMenuCommand::Balance => WorktimeCommand::Balance { since: None },
             MainMenuCommand::Sql => WorktimeCommand::Sql,
+            MainMenuCommand::Doctor => WorktimeCommand::Doctor,
+            MainMenuCommand::Repair => WorktimeCommand::Repair { yes: false },
             MainMenuCommand::Help => WorktimeCommand::InternalHelp,
             MainMenuCommand::Quit => WorktimeCommand::Quit,
+            MainMenuCommand::List => WorktimeCommand::List {
+                limit: 20,
+                offset: 0,
+                tz_shift: None,
+            },
+            MainMenuCommand::Add => self.prompt_add().await,
             MainMenuCommand::Correct => self.prompt_correct(db).await,
+            MainMenuCommand::Delete => self.prompt_delete(db).await,
         }
     }
 
-    async fn prompt_report(&self) -> WorktimeCommand {
+    async fn prompt_report(&self, db: &WorktimeDatabase) -> WorktimeCommand {
         let kind = *prompt_selection(
             "What report do you want, bruv?",
             &ReportKind::wrapped_iter().collect::<Vec<ReportKind>>(),
         );
 
-        WorktimeCommand::Report { kind }
+        let projects = db
+            .get_distinct_projects()
+            .await
+            .expect("Failed to query projects");
+        let project = match projects.is_empty() {
+            true => None,
+            false => {
+                let choices: Vec<String> = ["all projects".to_string()]
+                    .into_iter()
+                    .chain(projects)
+                    .collect();
+                let choice = prompt_selection("Which project, bruv?", &choices);
+                (choice != &choices[0]).then(|| choice.clone())
+            }
+        };
+
+        WorktimeCommand::Report {
+            kind,
+            options: ReportOptions {
+                project,
+                ..Default::default()
+            },
+        }
     }
 
     async fn prompt_correct(&self, db: &WorktimeDatabase) -> WorktimeCommand {
-        let last_sessions = db
-            .get_last_n_sessions_desc(10)
-            .await
-            .expect("Failed to query previous sessions");
-        let session = prompt_selection("Which entry do you want to correct, bruv?", &last_sessions);
+        let (nth_last, session) =
+            prompt_session(db, "Which entry do you want to correct, bruv?").await;
+        let date = confirm("Move it to another day?").then(|| {
+            Input::<NaiveDate>::with_theme(&*THEME)
+                .with_prompt("Which day (YYYY-MM-DD)?")
+                .default(session.start.date())
+                .interact_text()
+                .expect("Failed to read input")
+        });
 
         let kind = *prompt_selection(
             "Start or end?",
@@ -84,15 +141,11 @@ impl StdIn for RealStdIn {
                 let updated_min = h as u32 * 60 + m as u32;
 
                 match (kind, end_min) {
-                    (CorrectionKind::Start, Some(end_min)) => {
-                        if updated_min > end_min {
-                            return Err("Start can't be after end!".to_string());
-                        }
+                    (CorrectionKind::Start, Some(end_min)) if updated_min > end_min => {
+                        return Err("Start can't be after end!".to_string());
                     }
-                    (CorrectionKind::End, _) => {
-                        if updated_min < start_min {
-                            return Err("Start can't be after end!".to_string());
-                        }
+                    (CorrectionKind::End, _) if updated_min < start_min => {
+                        return Err("Start can't be after end!".to_string());
                     }
                     _ => {}
                 }
@@ -106,15 +159,46 @@ impl StdIn for RealStdIn {
             parse_hhmm(&time_input).expect("user-input should be validated already");
 
         WorktimeCommand::Correct {
-            nth_last: last_sessions
-                .iter()
-                .position(|s| s.id == session.id)
-                .unwrap() as u32,
+            nth_last,
             kind,
-            hours,
-            minutes,
+            hours: hours.into(),
+            minutes: minutes.into(),
+            relative: false,
+            date,
         }
     }
+
+    async fn prompt_delete(&self, db: &WorktimeDatabase) -> WorktimeCommand {
+        let (nth_last, _) = prompt_session(db, "Which entry do you want to delete, bruv?").await;
+
+        WorktimeCommand::Delete {
+            nth_last,
+            yes: false,
+        }
+    }
+
+    async fn prompt_add(&self) -> WorktimeCommand {
+        let date: NaiveDate = Input::with_theme(&*THEME)
+            .with_prompt("Which day (YYYY-MM-DD)?")
+            .interact_text()
+            .expect("Failed to read input");
+        let start = prompt_hhmm("Start (HH:MM)", None);
+        let end = prompt_hhmm("End (HH:MM)", Some(start));
+
+        WorktimeCommand::Add { date, start, end }
+    }
+
+    async fn confirm(&self, prompt: &str) -> bool {
+        confirm(prompt)
+    }
+
+    async fn prompt_more(&self) -> bool {
+        Confirm::with_theme(&*THEME)
+            .with_prompt("Show more?")
+            .default(true)
+            .interact()
+            .expect("Failed to read input")
+    }
 }
 
 //##########################################################
@@ -122,6 +206,45 @@ impl StdIn for RealStdIn {
 //##########################################################
 
 static THEME: LazyLock<ColorfulTheme> = LazyLock::new(ColorfulTheme::default);
+/// pages of 10 sessions (newest first) with a "Load more…" entry while there are more;
+/// returns the pick's global position (`nth_last`)
+async fn prompt_session(db: &WorktimeDatabase, prompt: &str) -> (u32, WorktimeSession) {
+    const PAGE: u32 = 10;
+    let total = db
+        .count_sessions()
+        .await
+        .expect("Failed to count previous sessions");
+    let pages = total.div_ceil(PAGE).max(1);
+    let mut sessions: Vec<WorktimeSession> = Vec::new();
+    let mut default = 0;
+    loop {
+        let loaded = sessions.len() as u32;
+        if loaded < total {
+            let page = db
+                .get_sessions_paged(PAGE, loaded)
+                .await
+                .expect("Failed to query previous sessions");
+            sessions.extend(page);
+        }
+        let more = (sessions.len() as u32) < total;
+        let mut items: Vec<String> = sessions.iter().map(ToString::to_string).collect();
+        if more {
+            items.push("Load more…".to_string());
+        }
+        let page = (sessions.len() as u32).div_ceil(PAGE).max(1);
+        let idx = Select::with_theme(&*THEME)
+            .default(default)
+            .with_prompt(format!("{prompt} (page {page}/{pages})"))
+            .items(&items)
+            .interact()
+            .expect("Can't print choices");
+        match sessions.get(idx) {
+            Some(session) => return (idx as u32, session.clone()),
+            None => default = idx,
+        }
+    }
+}
+
 fn prompt_selection<'item, T: ToString>(prompt: &str, items: &'item [T]) -> &'item T {
     let idx = Select::with_theme(&*THEME)
         .default(0)
@@ -132,6 +255,32 @@ fn prompt_selection<'item, T: ToString>(prompt: &str, items: &'item [T]) -> &'it
     items.get(idx).expect("selection can never be out of range")
 }
 
+/// defaults to no; also no if there's no terminal to ask (use `--yes` in scripts)
+fn confirm(prompt: &str) -> bool {
+    Confirm::with_theme(&*THEME)
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .unwrap_or(false)
+}
+
+/// `not_before` rejects times earlier than it
+fn prompt_hhmm(prompt: &str, not_before: Option<NaiveTime>) -> NaiveTime {
+    let to_time = |(h, m): (u8, u8)| NaiveTime::from_hms_opt(h.into(), m.into(), 0).unwrap();
+    let input: String = Input::with_theme(&*THEME)
+        .with_prompt(prompt)
+        .validate_with(|s: &String| -> Result<(), String> {
+            let time = to_time(parse_hhmm(s)?);
+            match not_before {
+                Some(start) if time < start => Err("End can't be before start!".to_string()),
+                _ => Ok(()),
+            }
+        })
+        .interact_text()
+        .expect("Failed to read input");
+    to_time(parse_hhmm(&input).expect("user-input should be validated already"))
+}
+
 /// returns (hours, minutes)
 fn parse_hhmm(s: &str) -> Result<(u8, u8), String> {
     let (h, m) = s
@@ -154,26 +303,42 @@ fn parse_hhmm(s: &str) -> Result<(u8, u8), String> {
 //##########################################################
 // Mock stdin
 //##########################################################
-#[cfg(test)]
-pub(crate) mod test_utils {
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils {
     use super::*;
+    use crate::stdout::ResultFormat;
     use std::{cell::RefCell, vec::IntoIter};
 
     pub struct MockStdIn {
         pub commands: RefCell<IntoIter<WorktimeCommand>>,
+        pub format: ResultFormat,
     }
 
     impl MockStdIn {
-        pub(crate) fn new(vec: Vec<WorktimeCommand>) -> Self {
+        pub fn new(vec: Vec<WorktimeCommand>) -> Self {
             Self {
                 commands: RefCell::new(vec.into_iter()),
+                format: ResultFormat::default(),
             }
         }
+
+        pub fn with_format(self, format: ResultFormat) -> Self {
+            Self { format, ..self }
+        }
     }
 
     impl StdIn for MockStdIn {
-        fn parse(&self) -> Option<WorktimeCommand> {
-            self.commands.borrow_mut().next()
+        fn parse(&self) -> Option<Cli> {
+            self.commands.borrow_mut().next().map(|command| Cli {
+                format: self.format,
+                no_color: false,
+                db: None,
+                dry_run: false,
+                verbose: false,
+                quiet: false,
+                output_file: None,
+                command,
+            })
         }
 
         async fn prompt(&self, _: &WorktimeDatabase) -> WorktimeCommand {
@@ -183,7 +348,7 @@ pub(crate) mod test_utils {
                 .unwrap_or(WorktimeCommand::Quit)
         }
 
-        async fn prompt_report(&self) -> WorktimeCommand {
+        async fn prompt_report(&self, _: &WorktimeDatabase) -> WorktimeCommand {
             self.commands
                 .borrow_mut()
                 .next()
@@ -196,6 +361,29 @@ pub(crate) mod test_utils {
                 .next()
                 .unwrap_or(WorktimeCommand::Quit)
         }
+
+        async fn prompt_delete(&self, _: &WorktimeDatabase) -> WorktimeCommand {
+            self.commands
+                .borrow_mut()
+                .next()
+                .unwrap_or(WorktimeCommand::Quit)
+        }
+
+        async fn prompt_add(&self) -> WorktimeCommand {
+            self.commands
+                .borrow_mut()
+                .next()
+                .unwrap_or(WorktimeCommand::Quit)
+        }
+
+        /// tests confirm by passing `yes: true` instead
+        async fn confirm(&self, _: &str) -> bool {
+            false
+        }
+
+        async fn prompt_more(&self) -> bool {
+            false
+        }
     }
 
     impl From<Vec<WorktimeCommand>> for MockStdIn {
@@ -1,52 +1,366 @@
-use crate::{cli::WorktimeCommand, err::CommandResult};
+use crate::{
+    cli::WorktimeCommand,
+    err::{CommandError, CommandOutput, CommandResult},
+};
+use std::{
+    env,
+    fs::OpenOptions,
+    io::{self, IsTerminal, Write},
+    path::Path,
+};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ResultFormat {
+    #[default]
+    Text,
+    Json,
+    // just the figure (e.g. `6.00` hours) without label or linebreaks; nothing on errors
+    Plain,
+}
 
 /// proxy for all stdout interaction for testability
 pub trait StdOut {
-    fn print(&mut self, cmd: WorktimeCommand, r: CommandResult);
+    fn print(
+        &mut self,
+        cmd: WorktimeCommand,
+        r: CommandResult<CommandOutput>,
+        format: ResultFormat,
+    );
+    /// `color` is the caller's wish; plain output wins when piped
+    fn print_table(&mut self, rows: &[TableRow], color: bool);
+}
+
+/// how a table row is colored
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    #[default]
+    Plain,
+    /// over target (green)
+    Over,
+    /// under target (red)
+    Under,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TableRow {
+    pub cells: Vec<String>,
+    pub tone: Tone,
+}
+
+impl From<Vec<String>> for TableRow {
+    fn from(cells: Vec<String>) -> Self {
+        Self {
+            cells,
+            tone: Tone::Plain,
+        }
+    }
+}
+
+struct RealStdOut {
+    out: Box<dyn Write>,
+    /// errors go to `out` as well instead of stderr
+    to_file: bool,
+    /// successful text results are dropped; `--format json|plain` still prints them
+    quiet: bool,
 }
 
-struct RealStdOut {}
+impl RealStdOut {
+    /// a closed pipe or full disk shouldn't abort the command (it already ran)
+    fn write(&mut self, text: &str) {
+        let _ = write!(self.out, "{text}");
+        let _ = self.out.flush();
+    }
+
+    fn error(&mut self, text: &str) {
+        match self.to_file {
+            true => self.write(&format!("{text}\n")),
+            false => eprintln!("{text}"),
+        }
+    }
+}
 
 impl StdOut for RealStdOut {
-    fn print(&mut self, cmd: WorktimeCommand, r: CommandResult) {
+    fn print(
+        &mut self,
+        cmd: WorktimeCommand,
+        r: CommandResult<CommandOutput>,
+        format: ResultFormat,
+    ) {
+        match (format, &r) {
+            (ResultFormat::Json, _) => {
+                self.write(&format!("{}\n", json_result(&cmd, &r)));
+                return;
+            }
+            (ResultFormat::Plain, Ok(output)) => {
+                self.write(&plain_result(output));
+                return;
+            }
+            (ResultFormat::Text, Ok(_)) if self.quiet => return,
+            _ => {}
+        }
         match r {
-            Ok(m) => println!("{m}"),
+            Ok(output) => self.write(&format!("{}\n", output.message)),
+            // plain errors only reach stderr (and the exit code)
             Err(e) => match e {
                 crate::err::CommandError::DatabaseError(error) => {
-                    eprintln!("{cmd:?} failed with: {error}");
+                    self.error(&format!("{cmd:?} failed with: {error}"));
                 }
                 crate::err::CommandError::Other(reason) => {
-                    eprintln!("{cmd:?} skipped due to: {reason}");
+                    self.error(&format!("{cmd:?} skipped due to: {reason}"));
                 }
             },
         }
-        add_linebrakes();
+        if format == ResultFormat::Text && !self.quiet {
+            self.write(LINEBREAKS);
+        }
+    }
+
+    fn print_table(&mut self, rows: &[TableRow], color: bool) {
+        let color = color
+            && !self.to_file
+            && io::stdout().is_terminal()
+            && env::var_os("NO_COLOR").is_none();
+        self.write(&format!("{}\n", render_table(rows, color)));
+        self.write(LINEBREAKS);
     }
 }
 
-pub fn add_linebrakes() {
-    print!("\n\n");
+/// columns padded to their widest cell, the first left- and the others right-aligned
+pub fn render_table(rows: &[TableRow], color: bool) -> String {
+    let columns = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.cells.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, width))| match i {
+                    0 => format!("{cell:<width$}"),
+                    _ => format!("{cell:>width$}"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            match (color, row.tone) {
+                (true, Tone::Over) => format!("\x1B[32m{line}\x1B[0m"),
+                (true, Tone::Under) => format!("\x1B[31m{line}\x1B[0m"),
+                _ => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-pub fn get_std_out() -> impl StdOut {
-    RealStdOut {}
+/// e.g. `{"command":"Start","ok":true,"message":"Start at 09:00","time":"09:00:00"}`
+fn json_result(cmd: &WorktimeCommand, r: &CommandResult<CommandOutput>) -> String {
+    let command: &'static str = cmd.into();
+    let mut json = format!(r#"{{"command":"{command}","ok":{}"#, r.is_ok());
+    match r {
+        Ok(output) => {
+            json.push_str(&format!(r#","message":{}"#, json_string(&output.message)));
+            for (key, value) in &output.fields {
+                json.push_str(&format!(r#","{key}":{value}"#));
+            }
+        }
+        Err(CommandError::DatabaseError(error)) => {
+            json.push_str(&format!(r#","error":{}"#, json_string(&error.to_string())));
+        }
+        Err(CommandError::Other(reason)) => {
+            json.push_str(&format!(r#","error":{}"#, json_string(reason)));
+        }
+    }
+    json.push('}');
+    json
+}
+
+/// the `hours` of a report, else the message as is
+fn plain_result(output: &CommandOutput) -> String {
+    output
+        .fields
+        .iter()
+        .find(|(key, _)| *key == "hours")
+        .map_or_else(|| output.message.clone(), |(_, hours)| hours.clone())
+}
+
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// separates the results of consecutive commands
+const LINEBREAKS: &str = "\n\n";
+
+/// prints to `output` (appending) instead of stdout/stderr if given
+pub fn get_std_out(output: Option<&Path>, quiet: bool) -> io::Result<impl StdOut> {
+    let out: Box<dyn Write> = match output {
+        Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+        None => Box::new(io::stdout()),
+    };
+    Ok(RealStdOut {
+        out,
+        to_file: output.is_some(),
+        quiet,
+    })
 }
 
 //##########################################################
 // Mock stdout
 //##########################################################
-#[cfg(test)]
-pub(crate) mod test_utils {
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils {
     use super::*;
 
     #[derive(Debug, Default)]
     pub struct StdOutRecorder {
         pub results: Vec<CommandResult>,
+        pub tables: Vec<Vec<TableRow>>,
     }
 
     impl StdOut for StdOutRecorder {
-        fn print(&mut self, _: WorktimeCommand, r: CommandResult) {
-            self.results.push(r);
+        fn print(&mut self, _: WorktimeCommand, r: CommandResult<CommandOutput>, _: ResultFormat) {
+            self.results.push(r.map(|output| output.message));
+        }
+
+        fn print_table(&mut self, rows: &[TableRow], _: bool) {
+            self.tables.push(rows.to_vec());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_aligned_table() {
+        let rows = vec![
+            TableRow::from(vec!["date".to_string(), "duration".to_string()]),
+            TableRow {
+                cells: vec!["total".to_string(), "8.00h".to_string()],
+                tone: Tone::Under,
+            },
+        ];
+
+        assert_eq!(
+            render_table(&rows, false),
+            "date   duration\ntotal     8.00h"
+        );
+        assert_eq!(
+            render_table(&rows, true).lines().last().unwrap(),
+            "\x1B[31mtotal     8.00h\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn should_append_results_and_errors_to_output_file() {
+        let path = env::temp_dir().join(format!("worktime-output-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cancel = || WorktimeCommand::Cancel { yes: true };
+
+        for _ in 0..2 {
+            let mut std_out = get_std_out(Some(&path), false).unwrap();
+            std_out.print(
+                cancel(),
+                Ok("Cancelled".to_string().into()),
+                ResultFormat::Text,
+            );
+            std_out.print(cancel(), Err("Nothing running".into()), ResultFormat::Text);
         }
+        let logged = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let once = "Cancelled\n\n\nCancel { yes: true } skipped due to: Nothing running\n\n\n";
+        assert_eq!(logged, once.repeat(2));
+    }
+
+    #[test]
+    fn should_only_print_errors_and_explicit_formats_when_quiet() {
+        let path = env::temp_dir().join(format!("worktime-quiet-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cancel = || WorktimeCommand::Cancel { yes: true };
+        let report =
+            || CommandOutput::from("Day's balance: 6.00h".to_string()).with("hours", "6.00");
+
+        let mut std_out = get_std_out(Some(&path), true).unwrap();
+        std_out.print(
+            cancel(),
+            Ok("Cancelled".to_string().into()),
+            ResultFormat::Text,
+        );
+        std_out.print(cancel(), Err("Nothing running".into()), ResultFormat::Text);
+        std_out.print(cancel(), Ok(report()), ResultFormat::Plain);
+        let logged = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            logged,
+            "Cancel { yes: true } skipped due to: Nothing running\n6.00"
+        );
+    }
+
+    #[test]
+    fn should_render_plain_results() {
+        let report = CommandOutput::from("Day's balance: 6.00h".to_string()).with("hours", "6.00");
+
+        assert_eq!(plain_result(&report), "6.00");
+        assert_eq!(
+            plain_result(&"Stop at 17:00".to_string().into()),
+            "Stop at 17:00"
+        );
+    }
+
+    #[test]
+    fn should_render_json_results() {
+        let start = CommandOutput::from("Start at 09:00".to_string()).with("time", r#""09:00:00""#);
+        let report = CommandOutput::from("Day's \"balance\"\n".to_string()).with("hours", "6.00");
+        let stop = WorktimeCommand::Stop {
+            threshold_alert: None,
+            trim_idle: false,
+        };
+
+        assert_eq!(
+            json_result(
+                &WorktimeCommand::Start {
+                    note: None,
+                    project: None,
+                },
+                &Ok(start)
+            ),
+            r#"{"command":"Start","ok":true,"message":"Start at 09:00","time":"09:00:00"}"#
+        );
+        assert_eq!(
+            json_result(
+                &WorktimeCommand::Start {
+                    note: None,
+                    project: None,
+                },
+                &Ok(report)
+            ),
+            r#"{"command":"Start","ok":true,"message":"Day's \"balance\"\n","hours":6.00}"#
+        );
+        assert_eq!(
+            json_result(&stop, &Err("No session started".into())),
+            r#"{"command":"Stop","ok":false,"error":"No session started"}"#
+        );
     }
 }
@@ -1,5 +1,12 @@
-use crate::db::WorktimeSession;
-use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, TimeDelta, Weekday};
+use crate::config::CONFIG;
+use crate::db::{WorktimeSession, WorktimeSessionId};
+use chrono::{
+    DateTime, Datelike, Days, IsoWeek, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta,
+    Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use std::slice;
+use strum::EnumString;
 
 //##########################################################
 // Clock
@@ -12,7 +19,15 @@ struct RealClock {}
 
 impl Clock for RealClock {
     fn get_now(&self) -> NaiveDateTime {
-        Local::now().naive_local()
+        local_time(Utc::now(), CONFIG.timezone)
+    }
+}
+
+/// wall-clock time of `utc` in `timezone` (the system zone if `None`)
+pub fn local_time(utc: DateTime<Utc>, timezone: Option<Tz>) -> NaiveDateTime {
+    match timezone {
+        Some(tz) => utc.with_timezone(&tz).naive_local(),
+        None => utc.with_timezone(&Local).naive_local(),
     }
 }
 
@@ -27,40 +42,591 @@ pub fn get_today(clock: &impl Clock) -> NaiveDate {
     clock.get_now().date()
 }
 
-pub fn get_week_start(clock: &impl Clock) -> NaiveDate {
-    let today = get_today(clock);
-    let week_offset = today.weekday().days_since(Weekday::Mon);
-    today
-        .checked_sub_days(Days::new(week_offset.into()))
-        .unwrap()
+pub fn get_week_start(clock: &impl Clock, week_start: Weekday) -> NaiveDate {
+    week_start_of(get_today(clock), week_start)
 }
 
 pub fn get_month_start(clock: &impl Clock) -> NaiveDate {
+    month_start_of(get_today(clock))
+}
+
+pub fn get_year_start(clock: &impl Clock) -> NaiveDate {
+    year_start_of(get_today(clock))
+}
+
+/// the `week_start` day of the week containing `day`
+pub fn week_start_of(day: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let week_offset = day.weekday().days_since(week_start);
+    day.checked_sub_days(Days::new(week_offset.into())).unwrap()
+}
+
+/// first of the month containing `day`
+pub fn month_start_of(day: NaiveDate) -> NaiveDate {
+    day.checked_sub_days(Days::new(day.day0().into())).unwrap()
+}
+
+/// January 1st of the year containing `day`
+pub fn year_start_of(day: NaiveDate) -> NaiveDate {
+    day.with_ordinal(1).unwrap()
+}
+
+/// `YYYY-MM-DD`, `today`, `yesterday`, `this-week`, `last-week` (their first day),
+/// `this-month` or an offset from today like `-3d`/`-1w`
+pub fn parse_date_spec(spec: &str, clock: &impl Clock) -> Result<NaiveDate, String> {
     let today = get_today(clock);
-    let month_offset = today.day0();
-    today
-        .checked_sub_days(Days::new(month_offset.into()))
-        .unwrap()
+    let day = match spec.trim().to_lowercase().as_str() {
+        "today" => Some(today),
+        "yesterday" => today.checked_sub_days(Days::new(1)),
+        "this-week" => Some(get_week_start(clock, CONFIG.week_start)),
+        "last-week" => get_week_start(clock, CONFIG.week_start).checked_sub_days(Days::new(7)),
+        "this-month" => Some(get_month_start(clock)),
+        offset if offset.starts_with(['-', '+']) => {
+            let (count, days) = match (offset.strip_suffix('d'), offset.strip_suffix('w')) {
+                (Some(count), _) => (count, 1),
+                (_, Some(count)) => (count, 7),
+                _ => return Err(format!("Unknown unit in '{spec}', use e.g. -3d or -1w")),
+            };
+            let count: i64 = count
+                .parse()
+                .map_err(|_| format!("Invalid offset '{spec}', use e.g. -3d or -1w"))?;
+            count
+                .checked_mul(days)
+                .and_then(TimeDelta::try_days)
+                .and_then(|delta| today.checked_add_signed(delta))
+        }
+        date => Some(date.parse().map_err(|_| {
+            format!("Invalid date '{spec}', use YYYY-MM-DD, today, yesterday, this-week, last-week, this-month or e.g. -3d")
+        })?),
+    };
+    day.ok_or_else(|| format!("'{spec}' is out of range"))
 }
 
 //##########################################################
 // Other utilities (not dependent on NOW)
 //##########################################################
 pub fn aggregate_session_times(sessions: &[WorktimeSession], now: NaiveDateTime) -> TimeDelta {
-    sessions.iter().fold(
-        TimeDelta::zero(),
-        |curr, WorktimeSession { id: _, start, end }| {
-            let start = *start;
-            let end = end.unwrap_or(now);
-            curr + (end - start)
-        },
+    sessions.iter().map(|session| session.duration(now)).sum()
+}
+
+/// the session's worked time per calendar day it touches (e.g. 23:30-00:30 is 0.5h on each day)
+pub fn split_at_midnight(
+    session: &WorktimeSession,
+    now: NaiveDateTime,
+) -> Vec<(NaiveDate, TimeDelta)> {
+    let end = session.end.unwrap_or(now);
+    let mut parts = split_interval(session.start, end);
+    for pause in &session.breaks {
+        for (day, paused) in split_interval(pause.start, pause.end.unwrap_or(end)) {
+            if let Some((_, worked)) = parts.iter_mut().find(|(d, _)| *d == day) {
+                *worked -= paused;
+            }
+        }
+    }
+    parts
+}
+
+fn split_interval(mut start: NaiveDateTime, end: NaiveDateTime) -> Vec<(NaiveDate, TimeDelta)> {
+    let mut parts = Vec::new();
+    while start.date() < end.date() {
+        let midnight = (start.date() + Days::new(1)).and_time(NaiveTime::MIN);
+        parts.push((start.date(), midnight - start));
+        start = midnight;
+    }
+    parts.push((start.date(), end - start));
+    parts
+}
+
+/// only the time of `sessions` that falls within the days `[from, to]`
+pub fn aggregate_session_times_between(
+    sessions: &[WorktimeSession],
+    from: NaiveDate,
+    to: NaiveDate,
+    now: NaiveDateTime,
+) -> TimeDelta {
+    let window = (
+        from.and_time(NaiveTime::MIN),
+        (to + Days::new(1)).and_time(NaiveTime::MIN),
+    );
+    aggregate_session_times_within(sessions, window, now)
+}
+
+/// the time of `sessions` (minus breaks) clamped to `[from, to)`; open sessions run until `now`
+pub fn aggregate_session_times_within(
+    sessions: &[WorktimeSession],
+    (from, to): (NaiveDateTime, NaiveDateTime),
+    now: NaiveDateTime,
+) -> TimeDelta {
+    let clamped = |start: NaiveDateTime, end: NaiveDateTime| {
+        (end.min(to) - start.max(from)).max(TimeDelta::zero())
+    };
+    sessions
+        .iter()
+        .map(|session| {
+            let end = session.end.unwrap_or(now);
+            let paused: TimeDelta = session
+                .breaks
+                .iter()
+                .map(|pause| clamped(pause.start, pause.end.unwrap_or(end)))
+                .sum();
+            clamped(session.start, end) - paused
+        })
+        .sum()
+}
+
+/// [`aggregate_session_times_between`] rounded to `granularity`, per session or as a total
+pub fn rounded_session_times_between(
+    sessions: &[WorktimeSession],
+    (from, to): (NaiveDate, NaiveDate),
+    now: NaiveDateTime,
+    granularity: TimeDelta,
+    round_each: bool,
+) -> TimeDelta {
+    if round_each {
+        sessions
+            .iter()
+            .map(|session| {
+                let delta =
+                    aggregate_session_times_between(slice::from_ref(session), from, to, now);
+                round_delta(delta, granularity)
+            })
+            .sum()
+    } else {
+        round_delta(
+            aggregate_session_times_between(sessions, from, to, now),
+            granularity,
+        )
+    }
+}
+
+/// tracked time per day of `[from, to]`
+pub fn daily_totals(
+    sessions: &[WorktimeSession],
+    from: NaiveDate,
+    to: NaiveDate,
+    now: NaiveDateTime,
+) -> Vec<(NaiveDate, TimeDelta)> {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .map(|day| {
+            (
+                day,
+                aggregate_session_times_between(sessions, day, day, now),
+            )
+        })
+        .collect()
+}
+
+/// the days of `[from, to]` with time tracked on them (split at midnight), in order
+pub fn worked_days(
+    sessions: &[WorktimeSession],
+    (from, to): (NaiveDate, NaiveDate),
+    now: NaiveDateTime,
+) -> Vec<(NaiveDate, TimeDelta)> {
+    let mut days: Vec<(NaiveDate, TimeDelta)> = Vec::new();
+    for (day, delta) in sessions
+        .iter()
+        .flat_map(|session| split_at_midnight(session, now))
+        .filter(|(day, _)| (from..=to).contains(day))
+    {
+        match days.iter_mut().find(|(d, _)| *d == day) {
+            Some((_, worked)) => *worked += delta,
+            None => days.push((day, delta)),
+        }
+    }
+    days.retain(|(_, worked)| *worked > TimeDelta::zero());
+    days.sort_by_key(|(day, _)| *day);
+    days
+}
+
+/// each value's whole-percent share of their sum, rounded so the shares add up to 100
+/// (largest remainders first); all 0 if the sum is
+pub fn percent_shares(values: &[f64]) -> Vec<u32> {
+    let total: f64 = values.iter().sum();
+    if total <= 0f64 {
+        return vec![0; values.len()];
+    }
+    let exact: Vec<f64> = values.iter().map(|v| v / total * 100f64).collect();
+    let mut shares: Vec<u32> = exact.iter().map(|e| e.floor() as u32).collect();
+    let mut by_remainder: Vec<usize> = (0..exact.len()).collect();
+    by_remainder.sort_by(|a, b| {
+        (exact[*b] - exact[*b].floor()).total_cmp(&(exact[*a] - exact[*a].floor()))
+    });
+    let missing = 100u32.saturating_sub(shares.iter().sum()) as usize;
+    for i in by_remainder.into_iter().take(missing) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// scales `values` (from 0 to their max) onto the eight block characters
+pub fn sparkline(values: &[f64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().fold(0f64, f64::max);
+    values
+        .iter()
+        .map(|v| {
+            if max > 0f64 {
+                BLOCKS[((v.max(0f64) / max) * 7f64).round() as usize]
+            } else {
+                BLOCKS[0]
+            }
+        })
+        .collect()
+}
+
+/// tracked time and working days per ISO week touched by `[from, to]`
+/// (sessions are attributed to the week they start in)
+pub fn weekly_totals(
+    sessions: &[WorktimeSession],
+    from: NaiveDate,
+    to: NaiveDate,
+    now: NaiveDateTime,
+) -> Vec<(IsoWeek, TimeDelta, u32)> {
+    let mut weeks: Vec<(IsoWeek, TimeDelta, u32)> = Vec::new();
+    for day in from.iter_days().take_while(|day| *day <= to) {
+        let working_day = u32::from(is_working_day(day));
+        match weeks.last_mut() {
+            Some((week, _, working_days)) if *week == day.iso_week() => {
+                *working_days += working_day;
+            }
+            _ => weeks.push((day.iso_week(), TimeDelta::zero(), working_day)),
+        }
+    }
+    for (day, delta) in sessions
+        .iter()
+        .flat_map(|session| split_at_midnight(session, now))
+        .filter(|(day, _)| (from..=to).contains(day))
+    {
+        let week = day.iso_week();
+        if let Some((_, tracked, _)) = weeks.iter_mut().find(|(w, _, _)| *w == week) {
+            *tracked += delta;
+        }
+    }
+    weeks
+}
+
+/// worked minutes bucketed by hour of day (0-23); open sessions run until `now`
+pub fn minutes_per_hour_of_day(sessions: &[WorktimeSession], now: NaiveDateTime) -> [i64; 24] {
+    let mut buckets = [0; 24];
+    for session in sessions {
+        let end = session.end.unwrap_or(now);
+        // the worked stretches around the breaks, so they add up to `session.duration(now)`
+        let mut stretches = Vec::new();
+        let mut from = session.start;
+        for pause in &session.breaks {
+            stretches.push((from, pause.start));
+            from = pause.end.unwrap_or(end);
+        }
+        stretches.push((from, end));
+        for (mut cursor, end) in stretches {
+            while cursor < end {
+                let hour_start = cursor
+                    .date()
+                    .and_hms_opt(cursor.hour(), 0, 0)
+                    .expect("valid hour");
+                let slice_end = (hour_start + TimeDelta::hours(1)).min(end);
+                buckets[cursor.hour() as usize] += (slice_end - cursor).num_minutes();
+                cursor = slice_end;
+            }
+        }
+    }
+    buckets
+}
+
+/// idle time between consecutive sessions of the same day as
+/// (session before, session after, gap)
+pub fn compute_gaps(
+    sessions: &[WorktimeSession],
+) -> Vec<(WorktimeSessionId, WorktimeSessionId, TimeDelta)> {
+    let mut sorted: Vec<&WorktimeSession> = sessions.iter().collect();
+    sorted.sort_by_key(|s| s.start);
+    sorted
+        .windows(2)
+        .filter_map(|pair| {
+            let (before, after) = (pair[0], pair[1]);
+            let end = before.end?;
+            (end.date() == after.start.date() && after.start > end)
+                .then(|| (before.id, after.id, after.start - end))
+        })
+        .collect()
+}
+
+/// warns if `now` lies before the latest time recorded for `last`
+/// (e.g. the system clock was adjusted backwards)
+pub fn clock_skew_warning(last: &WorktimeSession, now: NaiveDateTime) -> Option<String> {
+    let latest = last.end.unwrap_or(last.start).max(last.start);
+    (now < latest).then(|| {
+        format!(
+            "Warning: clock ({}) is behind the last recorded time ({}) - did it go backwards?",
+            now.format("%Y-%m-%d %H:%M"),
+            latest.format("%Y-%m-%d %H:%M")
+        )
+    })
+}
+
+/// warns if `last` is still running after more than `threshold` (e.g. forgot to stop yesterday)
+pub fn stale_session_warning(
+    last: &WorktimeSession,
+    now: NaiveDateTime,
+    threshold: TimeDelta,
+) -> Option<String> {
+    let running = now - last.start;
+    (last.is_open() && running > threshold).then(|| {
+        format!(
+            "Warning: session running since {} ({:.2}h) - forgot to stop? Use `stop` or `correct`",
+            last.start.format("%Y-%m-%d %H:%M"),
+            to_hours(running)
+        )
+    })
+}
+
+/// warns if nothing was seen of the running session for longer than `threshold`
+/// (e.g. the laptop slept while `watch` was running)
+pub fn idle_warning(
+    last_seen: NaiveDateTime,
+    now: NaiveDateTime,
+    threshold: TimeDelta,
+) -> Option<String> {
+    let idle = now - last_seen;
+    (idle > threshold).then(|| {
+        format!(
+            "Warning: idle since {} ({:.2}h) - asleep? Trim it with `correct 0 end {}`",
+            display_time(&last_seen),
+            to_hours(idle),
+            last_seen.format("%-H %-M")
+        )
+    })
+}
+
+/// a nudge if `start` looks accidental next to `recent`: more than an hour outside their usual
+/// hours (needs 5 of them) or over 4h after today's last stop
+pub fn unusual_start_note(start: NaiveDateTime, recent: &[WorktimeSession]) -> Option<String> {
+    let margin = TimeDelta::hours(1);
+    let closed: Vec<(NaiveDateTime, NaiveDateTime)> = recent
+        .iter()
+        .filter_map(|s| s.end.map(|end| (s.start, end)))
+        .collect();
+
+    let last_stop_today = closed
+        .iter()
+        .map(|(_, end)| *end)
+        .filter(|end| end.date() == start.date() && *end <= start)
+        .max();
+    if let Some(stop) = last_stop_today.filter(|stop| start - *stop > TimeDelta::hours(4)) {
+        return Some(format!(
+            "Note: second session today, {:.2}h after the last stop at {}",
+            to_hours(start - stop),
+            display_time(&stop)
+        ));
+    }
+
+    if closed.len() < 5 {
+        return None;
+    }
+    let earliest = closed.iter().map(|(s, _)| s.time()).min()?;
+    let latest = closed
+        .iter()
+        .filter(|(s, e)| s.date() == e.date())
+        .map(|(_, e)| e.time())
+        .max()?;
+    let (earliest, latest) = (
+        start.date().and_time(earliest),
+        start.date().and_time(latest),
+    );
+    (start < earliest - margin || start > latest + margin).then(|| {
+        format!(
+            "Note: unusual start - your sessions usually run {}-{}",
+            display_time(&earliest),
+            display_time(&latest)
+        )
+    })
+}
+
+/// length statistics of a period's sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionStats {
+    pub longest: TimeDelta,
+    pub shortest: TimeDelta,
+    pub average: TimeDelta,
+    pub count: usize,
+}
+
+/// lengths exclude breaks, open sessions run until `now`; `None` without sessions
+pub fn session_stats(sessions: &[WorktimeSession], now: NaiveDateTime) -> Option<SessionStats> {
+    let lengths: Vec<TimeDelta> = sessions.iter().map(|s| s.duration(now)).collect();
+    let total: TimeDelta = lengths.iter().copied().sum();
+    Some(SessionStats {
+        longest: *lengths.iter().max()?,
+        shortest: *lengths.iter().min()?,
+        average: total / lengths.len() as i32,
+        count: lengths.len(),
+    })
+}
+
+/// sessions (id & duration) that ran longer than `threshold`; open sessions run until `now`
+pub fn sessions_exceeding(
+    sessions: &[WorktimeSession],
+    threshold: TimeDelta,
+    now: NaiveDateTime,
+) -> Vec<(WorktimeSessionId, TimeDelta)> {
+    sessions
+        .iter()
+        .map(|s| (s.id, s.duration(now)))
+        .filter(|(_, duration)| *duration > threshold)
+        .collect()
+}
+
+pub fn display_threshold_alert(
+    (id, duration): (WorktimeSessionId, TimeDelta),
+    threshold: TimeDelta,
+) -> String {
+    format!(
+        "Warning: session '{id}' ran {:.2}h (threshold {:.2}h)",
+        to_hours(duration),
+        to_hours(threshold)
+    )
+}
+
+/// working days needed to reach `goal` at `pace` per day;
+/// `Some(0)` if the goal is already met, `None` without any pace
+pub fn goal_eta_days(tracked: TimeDelta, goal: TimeDelta, pace: TimeDelta) -> Option<u32> {
+    let left = goal - tracked;
+    if left <= TimeDelta::zero() {
+        return Some(0);
+    }
+    if pace <= TimeDelta::zero() {
+        return None;
+    }
+    let days = (left.num_minutes() as u64).div_ceil(pace.num_minutes() as u64);
+    Some(days as u32)
+}
+
+/// `daily_target` for every working day in `[from, to]`
+pub fn expected_hours(daily_target: f64, from: NaiveDate, to: NaiveDate) -> f64 {
+    daily_target * count_working_days(from, to) as f64
+}
+
+/// the hours `schedule` (Mon first) expects of `[from, to]`; `days_off` expect nothing
+pub fn scheduled_hours(
+    schedule: &[f64; 7],
+    (from, to): (NaiveDate, NaiveDate),
+    days_off: &[NaiveDate],
+) -> f64 {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .filter(|day| !days_off.contains(day))
+        .map(|day| schedule[day.weekday().num_days_from_monday() as usize])
+        .sum()
+}
+
+/// worked − expected hours over `[from, to]`; weekends and `holidays` expect nothing
+pub fn flex_balance(
+    daily: &[(NaiveDate, TimeDelta)],
+    (from, to): (NaiveDate, NaiveDate),
+    daily_target: f64,
+    holidays: &[NaiveDate],
+) -> f64 {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .fold(0f64, |balance, day| {
+            let worked: TimeDelta = daily
+                .iter()
+                .filter(|(d, _)| *d == day)
+                .map(|(_, delta)| *delta)
+                .sum();
+            let expected = match is_working_day(day) && !holidays.contains(&day) {
+                true => daily_target,
+                false => 0f64,
+            };
+            balance + to_hours(worked) - expected
+        })
+}
+
+/// the `n`-th working day after `from`
+pub fn add_working_days(from: NaiveDate, n: u32) -> NaiveDate {
+    from.iter_days()
+        .skip(1)
+        .filter(|day| is_working_day(*day))
+        .nth(n.saturating_sub(1) as usize)
+        .expect("NaiveDate::MAX is out of reach")
+}
+
+/// rounds `delta` to the nearest multiple of `granularity`, halves up (zero granularity is a no-op)
+pub fn round_delta(delta: TimeDelta, granularity: TimeDelta) -> TimeDelta {
+    let step = granularity.num_seconds();
+    if step <= 0 {
+        return delta;
+    }
+    TimeDelta::seconds((delta.num_seconds() + step / 2).div_euclid(step) * step)
+}
+
+/// rounds `delta` up to the next multiple of `granularity` (zero granularity is a no-op)
+pub fn round_up_delta(delta: TimeDelta, granularity: TimeDelta) -> TimeDelta {
+    let step = granularity.num_seconds();
+    if step <= 0 {
+        return delta;
+    }
+    let rounded = delta.num_seconds().div_euclid(step) * step;
+    let rounded = if rounded < delta.num_seconds() {
+        rounded + step
+    } else {
+        rounded
+    };
+    TimeDelta::seconds(rounded)
+}
+
+pub fn to_hours(delta: TimeDelta) -> f64 {
+    delta.num_minutes() as f64 / 60f64
+}
+
+pub fn is_working_day(day: NaiveDate) -> bool {
+    !matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// working days in `[from, to]` (both inclusive)
+pub fn count_working_days(from: NaiveDate, to: NaiveDate) -> u32 {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .filter(|day| is_working_day(*day))
+        .count() as u32
+}
+
+/// `HH:MM:SS` (hours may exceed 24)
+pub fn display_elapsed(delta: TimeDelta) -> String {
+    let seconds = delta.num_seconds().max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
     )
 }
 
+/// `HH:MM` or `hh:MM AM` depending on [`crate::config::Config::time_format`]
 pub fn display_time(
     time: &NaiveDateTime,
 ) -> chrono::format::DelayedFormat<chrono::format::StrftimeItems<'_>> {
-    time.format("%H:%M")
+    format_time(time, CONFIG.time_format)
+}
+
+pub fn format_time(
+    time: &NaiveDateTime,
+    format: TimeFormat,
+) -> chrono::format::DelayedFormat<chrono::format::StrftimeItems<'static>> {
+    match format {
+        TimeFormat::H24 => time.format("%H:%M"),
+        TimeFormat::H12 => time.format("%I:%M %p"),
+    }
+}
+
+/// clock style for displayed times (`WORKTIME_TIME_FORMAT=12h|24h`)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum TimeFormat {
+    #[default]
+    #[strum(serialize = "24h")]
+    H24,
+    #[strum(serialize = "12h")]
+    H12,
 }
 
 #[cfg(test)]
@@ -71,7 +637,7 @@ pub(crate) mod tests {
     fn should_get_week_start_from_wed() {
         let clock = MockClock::default();
         clock.set(9, 12, 0); // Wednesday
-        let actual = get_week_start(&clock);
+        let actual = get_week_start(&clock, Weekday::Mon);
         let expected = NaiveDate::from_ymd_opt(2025, 7, 7).unwrap();
 
         assert_eq!(actual, expected);
@@ -80,18 +646,499 @@ pub(crate) mod tests {
     fn should_get_week_start_from_sun() {
         let clock = MockClock::default();
         clock.set(13, 12, 0); // Sunday
-        let actual = get_week_start(&clock);
+        let actual = get_week_start(&clock, Weekday::Mon);
         let expected = NaiveDate::from_ymd_opt(2025, 7, 7).unwrap();
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn should_get_sunday_week_start_from_wed() {
+        let clock = MockClock::default();
+        clock.set(9, 12, 0); // Wednesday
+        let actual = get_week_start(&clock, Weekday::Sun);
+        let expected = NaiveDate::from_ymd_opt(2025, 7, 6).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_get_sunday_week_start_from_sun() {
+        let clock = MockClock::default();
+        clock.set(13, 12, 0); // Sunday
+        let actual = get_week_start(&clock, Weekday::Sun);
+        let expected = NaiveDate::from_ymd_opt(2025, 7, 13).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_get_week_start_across_year_boundary() {
+        let wed = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let mon = NaiveDate::from_ymd_opt(2024, 12, 30).unwrap();
+
+        assert_eq!(week_start_of(wed, Weekday::Mon), mon);
+        assert_eq!(week_start_of(mon, Weekday::Mon), mon);
+    }
+
+    #[test]
+    fn should_get_month_start() {
+        let leap_day = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let first = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+
+        assert_eq!(month_start_of(leap_day), first);
+        assert_eq!(month_start_of(first), first);
+    }
+
+    #[test]
+    fn should_get_year_start_from_mid_year() {
+        let clock = MockClock::default();
+        clock.set(9, 12, 0);
+        let actual = get_year_start(&clock);
+        let expected = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_split_at_midnight() {
+        let clock = MockClock::default();
+        let overnight =
+            WorktimeSession::new(1u32.into(), clock.get(7, 23, 30), Some(clock.get(8, 0, 30)));
+        let same_day =
+            WorktimeSession::new(2u32.into(), clock.get(8, 9, 0), Some(clock.get(8, 17, 0)));
+        let now = clock.get(8, 18, 0);
+
+        assert_eq!(
+            split_at_midnight(&overnight, now),
+            vec![
+                (clock.get(7, 0, 0).date(), TimeDelta::minutes(30)),
+                (clock.get(8, 0, 0).date(), TimeDelta::minutes(30)),
+            ]
+        );
+        assert_eq!(
+            split_at_midnight(&same_day, now),
+            vec![(clock.get(8, 0, 0).date(), TimeDelta::hours(8))]
+        );
+    }
+
+    #[test]
+    fn should_total_worked_days_split_at_midnight() {
+        let clock = MockClock::default();
+        let day = |d| clock.get(d, 0, 0).date();
+        let mut open = WorktimeSession::new(3u32.into(), clock.get(4, 9, 0), None);
+        open.breaks.push(crate::db::WorktimeBreak {
+            start: clock.get(4, 10, 0),
+            end: Some(clock.get(4, 10, 30)),
+        });
+        let sessions = [
+            WorktimeSession::new(1u32.into(), clock.get(2, 22, 0), Some(clock.get(3, 2, 0))),
+            WorktimeSession::new(2u32.into(), clock.get(3, 13, 0), Some(clock.get(3, 17, 30))),
+            open,
+        ];
+
+        assert_eq!(
+            worked_days(&sessions, (day(1), day(31)), clock.get(4, 12, 0)),
+            vec![
+                (day(2), TimeDelta::hours(2)),
+                (day(3), TimeDelta::minutes(390)),
+                (day(4), TimeDelta::minutes(150)),
+            ]
+        );
+        assert_eq!(
+            worked_days(&sessions, (day(3), day(3)), clock.get(4, 12, 0)),
+            vec![(day(3), TimeDelta::minutes(390))]
+        );
+    }
+
+    #[test]
+    fn should_share_percentages_summing_to_100() {
+        assert_eq!(percent_shares(&[1.0, 6.0, 2.0]), [11, 67, 22]);
+        assert_eq!(percent_shares(&[1.0, 1.0, 1.0]), [34, 33, 33]);
+        assert_eq!(percent_shares(&[0.0, 0.0]), [0, 0]);
+        assert_eq!(percent_shares(&[]), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn should_sum_scheduled_hours_without_days_off() {
+        let clock = MockClock::default();
+        let schedule = [8.0, 8.0, 4.0, 8.0, 6.0, 0.0, 0.0];
+        let week = (clock.get(7, 0, 0).date(), clock.get(13, 0, 0).date());
+
+        let full = scheduled_hours(&schedule, week, &[]);
+        let with_holiday = scheduled_hours(&schedule, week, &[clock.get(9, 0, 0).date()]);
+
+        assert_eq!(full, 34.0);
+        assert_eq!(with_holiday, 30.0);
+        // 32h tracked against the mid-week holiday
+        assert_eq!(32.0 - with_holiday, 2.0);
+    }
+
+    #[test]
+    fn should_count_working_days() {
+        let mon = NaiveDate::from_ymd_opt(2025, 7, 7).unwrap();
+        let sun = NaiveDate::from_ymd_opt(2025, 7, 13).unwrap();
+
+        assert_eq!(count_working_days(mon, sun), 5);
+        assert_eq!(count_working_days(mon, mon), 1);
+        assert_eq!(count_working_days(sun, mon), 0);
+    }
+
+    #[test]
+    fn should_bucket_minutes_per_hour() {
+        let clock = MockClock::default();
+        let sessions = [
+            WorktimeSession::new(1u32.into(), clock.get(7, 9, 0), Some(clock.get(7, 11, 0))),
+            WorktimeSession::new(2u32.into(), clock.get(7, 23, 30), Some(clock.get(8, 0, 45))),
+            WorktimeSession::new(3u32.into(), clock.get(8, 13, 15), None),
+        ];
+
+        let buckets = minutes_per_hour_of_day(&sessions, clock.get(8, 14, 10));
+
+        assert_eq!(buckets[9], 60);
+        assert_eq!(buckets[10], 60);
+        assert_eq!(buckets[11], 0);
+        assert_eq!(buckets[23], 30);
+        assert_eq!(buckets[0], 45);
+        assert_eq!(buckets[13], 45);
+        assert_eq!(buckets[14], 10);
+        assert_eq!(buckets.iter().sum::<i64>(), 250);
+    }
+
+    #[test]
+    fn should_leave_breaks_out_of_buckets_and_alerts() {
+        let clock = MockClock::default();
+        let mut paused =
+            WorktimeSession::new(1u32.into(), clock.get(7, 9, 0), Some(clock.get(7, 11, 0)));
+        paused.breaks.push(crate::db::WorktimeBreak {
+            start: clock.get(7, 10, 15),
+            end: Some(clock.get(7, 10, 45)),
+        });
+        let sessions = [paused];
+        let now = clock.get(7, 12, 0);
+
+        let buckets = minutes_per_hour_of_day(&sessions, now);
+        let alerts = sessions_exceeding(&sessions, TimeDelta::minutes(100), now);
+
+        assert_eq!(buckets[9], 60);
+        assert_eq!(buckets[10], 30);
+        assert_eq!(buckets.iter().sum::<i64>(), 90);
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn should_compute_gaps() {
+        let clock = MockClock::default();
+        let sessions = [
+            WorktimeSession::new(1u32.into(), clock.get(7, 8, 0), Some(clock.get(7, 10, 0))),
+            WorktimeSession::new(2u32.into(), clock.get(7, 12, 0), Some(clock.get(7, 13, 0))),
+            WorktimeSession::new(3u32.into(), clock.get(7, 13, 0), Some(clock.get(7, 17, 0))),
+            WorktimeSession::new(4u32.into(), clock.get(8, 9, 0), None),
+        ];
+
+        let gaps = compute_gaps(&sessions);
+
+        assert_eq!(gaps, vec![(1u32.into(), 2u32.into(), TimeDelta::hours(2))]);
+    }
+
+    #[test]
+    fn should_read_local_time_across_dst() {
+        let berlin = Some(chrono_tz::Europe::Berlin);
+        let utc = |h, m| {
+            NaiveDate::from_ymd_opt(2025, 3, 30)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let local = |h, m| {
+            NaiveDate::from_ymd_opt(2025, 3, 30)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+        };
+
+        // CET (+1) until 01:00 UTC, CEST (+2) afterwards
+        assert_eq!(local_time(utc(0, 30), berlin), local(1, 30));
+        assert_eq!(local_time(utc(1, 30), berlin), local(3, 30));
+        assert_eq!(local_time(utc(1, 30), Some(chrono_tz::UTC)), local(1, 30));
+    }
+
+    #[test]
+    fn should_warn_about_stale_session() {
+        let clock = MockClock::default();
+        let open = WorktimeSession::new(1u32.into(), clock.get(1, 9, 0), None);
+        let closed =
+            WorktimeSession::new(2u32.into(), clock.get(1, 9, 0), Some(clock.get(1, 17, 0)));
+        let threshold = TimeDelta::hours(16);
+
+        assert_eq!(
+            stale_session_warning(&open, clock.get(1, 18, 0), threshold),
+            None
+        );
+        assert_eq!(
+            stale_session_warning(&open, clock.get(2, 11, 0), threshold),
+            Some("Warning: session running since 2025-07-01 09:00 (26.00h) - forgot to stop? Use `stop` or `correct`".to_string())
+        );
+        assert_eq!(
+            stale_session_warning(&closed, clock.get(2, 11, 0), threshold),
+            None
+        );
+    }
+
+    #[test]
+    fn should_parse_date_specs() {
+        let clock = MockClock::default();
+        clock.set(9, 12, 0); // Wednesday
+        let day = |d| clock.get(d, 0, 0).date();
+        let parse = |spec| parse_date_spec(spec, &clock);
+
+        assert_eq!(parse("today"), Ok(day(9)));
+        assert_eq!(parse("yesterday"), Ok(day(8)));
+        assert_eq!(parse("this-week"), Ok(day(7)));
+        assert_eq!(
+            parse("last-week"),
+            Ok(NaiveDate::from_ymd_opt(2025, 6, 30).unwrap())
+        );
+        assert_eq!(parse("this-month"), Ok(day(1)));
+        assert_eq!(parse("-3d"), Ok(day(6)));
+        assert_eq!(parse("-1w"), Ok(day(2)));
+        assert_eq!(parse("+2d"), Ok(day(11)));
+        assert_eq!(parse("2025-07-04"), Ok(day(4)));
+        assert!(parse("-3x").is_err());
+        assert!(parse("soon").is_err());
+    }
+
+    #[test]
+    fn should_reject_odd_offsets_without_panicking() {
+        let clock = MockClock::default();
+        let parse = |spec| parse_date_spec(spec, &clock);
+
+        assert!(parse("-1é").unwrap_err().starts_with("Unknown unit"));
+        assert_eq!(
+            parse("-99999999999999d"),
+            Err("'-99999999999999d' is out of range".to_string())
+        );
+        assert_eq!(
+            parse("+9223372036854775807w"),
+            Err("'+9223372036854775807w' is out of range".to_string())
+        );
+    }
+
+    #[test]
+    fn should_note_unusual_starts() {
+        let clock = MockClock::default();
+        let workdays: Vec<WorktimeSession> = (1..=5)
+            .map(|d| WorktimeSession::new(d.into(), clock.get(d, 9, 0), Some(clock.get(d, 17, 0))))
+            .collect();
+
+        assert_eq!(unusual_start_note(clock.get(8, 8, 30), &workdays), None);
+        assert_eq!(
+            unusual_start_note(clock.get(8, 3, 0), &workdays),
+            Some("Note: unusual start - your sessions usually run 09:00-17:00".to_string())
+        );
+        assert_eq!(unusual_start_note(clock.get(8, 3, 0), &workdays[..4]), None);
+        assert_eq!(
+            unusual_start_note(clock.get(5, 22, 0), &workdays[4..]),
+            Some("Note: second session today, 5.00h after the last stop at 17:00".to_string())
+        );
+    }
+
+    #[test]
+    fn should_warn_about_idle_gap() {
+        let clock = MockClock::default();
+        let threshold = TimeDelta::minutes(15);
+
+        assert_eq!(
+            idle_warning(clock.get(1, 12, 0), clock.get(1, 12, 10), threshold),
+            None
+        );
+        assert_eq!(
+            idle_warning(clock.get(1, 12, 5), clock.get(1, 14, 5), threshold),
+            Some(
+                "Warning: idle since 12:05 (2.00h) - asleep? Trim it with `correct 0 end 12 5`"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn should_format_time_in_12h_or_24h() {
+        let clock = MockClock::default();
+        let time = clock.get(1, 13, 5);
+
+        assert_eq!(format_time(&time, TimeFormat::H24).to_string(), "13:05");
+        assert_eq!(format_time(&time, TimeFormat::H12).to_string(), "01:05 PM");
+        assert_eq!("12h".parse(), Ok(TimeFormat::H12));
+    }
+
+    #[test]
+    fn should_compute_session_stats() {
+        let clock = MockClock::default();
+        let sessions = [
+            WorktimeSession::new(1u32.into(), clock.get(1, 8, 0), Some(clock.get(1, 12, 12))),
+            WorktimeSession::new(2u32.into(), clock.get(1, 13, 0), Some(clock.get(1, 13, 30))),
+            WorktimeSession::new(3u32.into(), clock.get(1, 14, 0), None),
+        ];
+
+        assert_eq!(
+            session_stats(&sessions, clock.get(1, 15, 36)),
+            Some(SessionStats {
+                longest: TimeDelta::minutes(252),
+                shortest: TimeDelta::minutes(30),
+                average: TimeDelta::minutes(126),
+                count: 3,
+            })
+        );
+        assert_eq!(session_stats(&[], clock.get(1, 15, 36)), None);
+    }
+
+    #[test]
+    fn should_accumulate_flex_balance() {
+        let clock = MockClock::default();
+        let day = |d| clock.get(d, 0, 0).date();
+        // Fri 4th 9h, Sat 5th 2h, Mon 7th holiday 1h, Tue 8th 7.5h
+        let daily = [
+            (day(4), TimeDelta::hours(9)),
+            (day(5), TimeDelta::hours(2)),
+            (day(7), TimeDelta::hours(1)),
+            (day(8), TimeDelta::minutes(450)),
+        ];
+
+        assert_eq!(flex_balance(&daily, (day(4), day(8)), 8.0, &[day(7)]), 3.5);
+        assert_eq!(flex_balance(&daily, (day(4), day(8)), 8.0, &[]), -4.5);
+    }
+
+    #[test]
+    fn should_clamp_sessions_to_window() {
+        let clock = MockClock::default();
+        let mut paused =
+            WorktimeSession::new(3u32.into(), clock.get(2, 13, 0), Some(clock.get(2, 18, 0)));
+        paused.breaks.push(crate::db::WorktimeBreak {
+            start: clock.get(2, 14, 0),
+            end: Some(clock.get(2, 16, 0)),
+        });
+        let sessions = [
+            // starts before the window
+            WorktimeSession::new(1u32.into(), clock.get(2, 8, 0), Some(clock.get(2, 11, 0))),
+            // break half inside the window
+            paused,
+            // open, running past the window
+            WorktimeSession::new(4u32.into(), clock.get(3, 9, 0), None),
+        ];
+        let window = (clock.get(2, 10, 0), clock.get(3, 10, 0));
+
+        // 1h + (5h - 2h break) + 1h
+        assert_eq!(
+            aggregate_session_times_within(&sessions, window, clock.get(3, 12, 0)),
+            TimeDelta::hours(5)
+        );
+        // only the 2nd's 15:00-16:00 break part and 16:00-17:00 worked fall in
+        assert_eq!(
+            aggregate_session_times_within(
+                &sessions,
+                (clock.get(2, 15, 0), clock.get(2, 17, 0)),
+                clock.get(3, 12, 0)
+            ),
+            TimeDelta::hours(1)
+        );
+        // nothing overlaps
+        assert_eq!(
+            aggregate_session_times_within(
+                &sessions,
+                (clock.get(1, 0, 0), clock.get(2, 0, 0)),
+                clock.get(3, 12, 0)
+            ),
+            TimeDelta::zero()
+        );
+    }
+
+    #[test]
+    fn should_round_delta_to_nearest() {
+        let quarter = TimeDelta::minutes(15);
+
+        assert_eq!(
+            round_delta(TimeDelta::minutes(7), quarter),
+            TimeDelta::zero()
+        );
+        assert_eq!(round_delta(TimeDelta::minutes(8), quarter), quarter);
+        assert_eq!(
+            round_delta(TimeDelta::minutes(7) + TimeDelta::seconds(30), quarter),
+            quarter
+        );
+        assert_eq!(
+            round_delta(TimeDelta::minutes(7), TimeDelta::zero()),
+            TimeDelta::minutes(7)
+        );
+    }
+
+    #[test]
+    fn should_round_each_session_or_total() {
+        let clock = MockClock::default();
+        let sessions = [
+            WorktimeSession::new(1u32.into(), clock.get(7, 9, 0), Some(clock.get(7, 9, 8))),
+            WorktimeSession::new(2u32.into(), clock.get(7, 10, 0), Some(clock.get(7, 10, 8))),
+        ];
+        let day = clock.get(7, 0, 0).date();
+        let rounded = |round_each| {
+            rounded_session_times_between(
+                &sessions,
+                (day, day),
+                clock.get(7, 12, 0),
+                TimeDelta::minutes(15),
+                round_each,
+            )
+        };
+
+        assert_eq!(rounded(true), TimeDelta::minutes(30));
+        assert_eq!(rounded(false), TimeDelta::minutes(15));
+    }
+
+    #[test]
+    fn should_round_up_delta() {
+        let quarter = TimeDelta::minutes(15);
+        let rounded = |h, m| round_up_delta(TimeDelta::hours(h) + TimeDelta::minutes(m), quarter);
+
+        assert_eq!(rounded(7, 7), TimeDelta::minutes(7 * 60 + 15));
+        assert_eq!(rounded(7, 15), TimeDelta::minutes(7 * 60 + 15));
+        assert_eq!(rounded(0, 0), TimeDelta::zero());
+        assert_eq!(
+            round_up_delta(TimeDelta::minutes(7), TimeDelta::zero()),
+            TimeDelta::minutes(7)
+        );
+    }
+
+    #[test]
+    fn should_render_sparkline() {
+        let ascending: Vec<f64> = (0..8).map(f64::from).collect();
+
+        assert_eq!(sparkline(&ascending), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&[0f64, 0f64, 0f64]), "▁▁▁");
+        assert_eq!(sparkline(&[6.5]), "█");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn should_project_goal_eta() {
+        let tracked = TimeDelta::hours(20);
+        let goal = TimeDelta::hours(40);
+        let pace = TimeDelta::hours(8);
+
+        assert_eq!(goal_eta_days(tracked, goal, pace), Some(3));
+        assert_eq!(goal_eta_days(goal, goal, pace), Some(0));
+        assert_eq!(goal_eta_days(tracked, goal, TimeDelta::zero()), None);
+
+        let wed = NaiveDate::from_ymd_opt(2025, 7, 9).unwrap();
+        let mon = NaiveDate::from_ymd_opt(2025, 7, 14).unwrap();
+        assert_eq!(add_working_days(wed, 3), mon);
+    }
 }
 
 //##########################################################
 // Mock clock
 //##########################################################
-#[cfg(test)]
-pub(crate) mod test_utils {
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils {
     use std::cell::RefCell;
 
     use super::*;
@@ -0,0 +1,1057 @@
+mod common;
+
+use chrono::TimeDelta;
+use common::*;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::{env, path::PathBuf, process::ExitCode};
+use worktime::{
+    MIGRATOR,
+    cli::{self, WorktimeCommand},
+    db::{WorktimeDatabase, WorktimeSession, get_test_worktime_db},
+    err, run_loop,
+    stdin::test_utils::MockStdIn,
+    stdout::ResultFormat,
+    time::Clock,
+};
+
+#[tokio::test]
+async fn should_stop_where_last_seen_with_trim_idle() {
+    let (clock, mut recorder, db) = setup().await;
+    clock.set(1, 9, 0);
+    db.insert_start(&clock, None, None).await.unwrap();
+    db.record_heartbeat(clock.get(1, 10, 30), TimeDelta::minutes(15))
+        .await
+        .unwrap();
+
+    clock.set(1, 17, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+        threshold_alert: None,
+        trim_idle: true,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let stop_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(stop_out, "Stop at 10:30");
+    let last = db.get_last_session().await.unwrap().unwrap();
+    assert_eq!(last.end, Some(clock.get(1, 10, 30)));
+}
+
+#[tokio::test]
+async fn should_merge_fragmented_day() {
+    let (clock, mut recorder, db) = setup().await;
+
+    for (start, end) in [(8, 10), (11, 12), (13, 17)] {
+        track(&clock, &db, &mut recorder, 1, (start, end)).await;
+    }
+    let first_id = db.get_nth_last_session(2).await.unwrap().id;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Merge {
+        date: Some(clock.get(1, 0, 0).date()),
+        first_nth: None,
+        second_nth: None,
+        preserve_total: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    assert_eq!(1, db.count_sessions().await.unwrap());
+    let merged = db.get_session_by_id(first_id).await.unwrap();
+    let expected = WorktimeSession::new(first_id, clock.get(1, 8, 0), Some(clock.get(1, 17, 0)));
+    assert_eq!(expected, merged);
+}
+
+#[tokio::test]
+async fn should_merge_two_back_to_back_sessions() {
+    let (clock, mut recorder, db) = setup().await;
+    for hours in [(9, 12), (12, 17), (18, 19)] {
+        track(&clock, &db, &mut recorder, 1, hours).await;
+    }
+    let first_id = db.get_nth_last_session(2).await.unwrap().id;
+    let merge = |first_nth, second_nth| WorktimeCommand::Merge {
+        date: None,
+        first_nth: Some(first_nth),
+        second_nth: Some(second_nth),
+        preserve_total: false,
+    };
+
+    let std_in = MockStdIn::from(vec![merge(1, 2), merge(0, 1)]);
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    assert_eq!(2, db.count_sessions().await.unwrap());
+    let merged = db.get_session_by_id(first_id).await.unwrap();
+    let expected = WorktimeSession::new(first_id, clock.get(1, 9, 0), Some(clock.get(1, 17, 0)));
+    assert_eq!(expected, merged);
+    assert!(matches!(
+        recorder.results.last().unwrap(),
+        Err(err::CommandError::Other(m)) if m.starts_with("Gap of 60min")
+    ));
+}
+
+#[tokio::test]
+async fn should_split_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    let id = db.get_last_session().await.unwrap().unwrap().id;
+    let split = |nth_last, hours| WorktimeCommand::Split {
+        nth_last,
+        hours,
+        minutes: 30,
+    };
+
+    let std_in = MockStdIn::from(vec![split(0, 12), split(0, 18)]);
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let second = db.get_last_session().await.unwrap().unwrap();
+    assert_eq!(
+        db.get_session_by_id(id).await.unwrap(),
+        WorktimeSession::new(id, clock.get(1, 9, 0), Some(clock.get(1, 12, 30)))
+    );
+    assert_eq!(
+        second,
+        WorktimeSession::new(second.id, clock.get(1, 12, 30), Some(clock.get(1, 17, 0)))
+    );
+    let results = &recorder.results[recorder.results.len() - 2..];
+    assert_eq!(
+        results[0].clone().unwrap(),
+        format!("Split '{id}' at 12:30 into '{id}' and '{}'", second.id)
+    );
+    assert!(matches!(
+        &results[1],
+        Err(err::CommandError::Other(m)) if m.starts_with("18:30 isn't within session")
+    ));
+}
+
+#[tokio::test]
+async fn should_print_status_json() {
+    let (clock, mut recorder, db) = setup().await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Status {
+        json: true,
+        watch: false,
+        detailed: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let idle_out = recorder.results.last().unwrap().clone().unwrap();
+
+    start_at(&clock, &db, &mut recorder, 1, (9, 12)).await;
+    clock.set(1, 12, 14);
+    let std_in: MockStdIn = vec![WorktimeCommand::Status {
+        json: true,
+        watch: false,
+        detailed: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let running_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(idle_out, r#"{"running":false}"#);
+    assert_eq!(
+        running_out,
+        r#"{"running":true,"since":"09:12","elapsed_minutes":182}"#
+    );
+}
+
+#[tokio::test]
+async fn should_nudge_start_relatively() {
+    let (clock, mut recorder, db) = setup().await;
+
+    start_at(&clock, &db, &mut recorder, 1, (9, 0)).await;
+    let id = db.get_last_session().await.unwrap().unwrap().id;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Correct {
+        nth_last: 0,
+        kind: cli::CorrectionKind::Start,
+        hours: 0,
+        minutes: -15,
+        relative: true,
+        date: None,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let corrected = db.get_session_by_id(id).await.unwrap();
+    assert_eq!(
+        WorktimeSession::new(id, clock.get(1, 8, 45), None),
+        corrected
+    );
+}
+
+#[tokio::test]
+async fn should_warn_when_clock_goes_backwards() {
+    let (clock, mut recorder, db) = setup().await;
+
+    start_at(&clock, &db, &mut recorder, 2, (9, 0)).await;
+    clock.set(2, 10, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Status {
+        json: false,
+        watch: false,
+        detailed: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let fine_out = recorder.results.last().unwrap().clone().unwrap();
+
+    clock.set(1, 23, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Status {
+        json: false,
+        watch: false,
+        detailed: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let skewed_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert!(!fine_out.contains("Warning"));
+    assert_ends_with(
+        skewed_out.as_str(),
+        "Warning: clock (2025-07-01 23:00) is behind the last recorded time (2025-07-02 09:00) - did it go backwards?",
+    );
+}
+
+#[tokio::test]
+async fn should_open_and_close_like_start_and_stop() {
+    use clap::Parser;
+
+    let (clock, mut recorder, db) = setup().await;
+    let parse = |arg: &str| cli::Cli::try_parse_from(["worktime", arg]).unwrap().command;
+
+    clock.set(1, 9, 00);
+    let std_in: MockStdIn = vec![parse("open")].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let opened = db.get_last_session().await.unwrap().unwrap();
+
+    clock.set(1, 17, 00);
+    let std_in: MockStdIn = vec![parse("close")].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let closed = db.get_last_session().await.unwrap().unwrap();
+
+    assert_eq!(
+        WorktimeSession::new(opened.id, clock.get(1, 9, 0), None),
+        opened
+    );
+    assert_eq!(
+        WorktimeSession::new(opened.id, clock.get(1, 9, 0), Some(clock.get(1, 17, 0))),
+        closed
+    );
+}
+
+#[tokio::test]
+async fn should_render_single_watch_frame() {
+    let (clock, mut recorder, db) = setup().await;
+
+    start_at(&clock, &db, &mut recorder, 1, (9, 0)).await;
+    clock.set(1, 11, 30);
+
+    let watch = WorktimeCommand::Status {
+        json: false,
+        watch: true,
+        detailed: false,
+    };
+    let frame = watch.status_frame(&db, &clock).await.unwrap();
+
+    assert_eq!(frame, "Running since 09:00\nElapsed: 02:30:00");
+}
+
+#[tokio::test]
+async fn should_report_dates_from_stdin() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 2, (9, 12)).await;
+    track(&clock, &db, &mut recorder, 3, (9, 10)).await;
+
+    let input = "2025-07-01\n2025-07-03\n".as_bytes();
+    let out = cli::dates_report(&db, input, clock.get_now())
+        .await
+        .unwrap();
+
+    assert_eq!(out, "2025-07-01: 8.00h\n2025-07-03: 1.00h");
+}
+
+#[tokio::test]
+async fn should_diagnose_in_memory_db() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Doctor].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap();
+    assert!(last_out.contains(&format!("[pass] migrations are current (version {latest})")));
+    assert!(last_out.contains("[pass] no corrupt data"));
+    assert!(last_out.contains("[warn] database is in memory"));
+}
+
+#[tokio::test]
+async fn should_delete_nth_last_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    track(&clock, &db, &mut recorder, 7, (12, 13)).await;
+    let std_in: MockStdIn = vec![WorktimeCommand::Delete {
+        nth_last: 1,
+        yes: true,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(last_out, "Deleted session 'id: 1;start: 09:00;end: 11:00'");
+    assert_eq!(db.count_sessions().await.unwrap(), 1);
+    assert_eq!(
+        db.get_last_session().await.unwrap().unwrap().id,
+        2u32.into()
+    );
+}
+
+#[tokio::test]
+async fn should_delete_running_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    clock.set(7, 12, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Start {
+            note: None,
+            project: None,
+        },
+        WorktimeCommand::Delete {
+            nth_last: 0,
+            yes: true,
+        },
+        WorktimeCommand::Status {
+            json: false,
+            watch: false,
+            detailed: false,
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(last_out, "Not running");
+    assert_eq!(db.count_sessions().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn should_list_sessions_paged() {
+    let (clock, mut recorder, db) = setup().await;
+    let list = |limit, offset| -> MockStdIn {
+        vec![WorktimeCommand::List {
+            limit,
+            offset,
+            tz_shift: None,
+        }]
+        .into()
+    };
+
+    run_loop(&clock, &db, &list(20, 0), &mut recorder).await;
+    let empty_out = recorder.results.last().unwrap().clone().unwrap();
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    track(&clock, &db, &mut recorder, 7, (12, 13)).await;
+    track(&clock, &db, &mut recorder, 8, (9, 17)).await;
+    run_loop(&clock, &db, &list(2, 0), &mut recorder).await;
+    let first_page = recorder.results.last().unwrap().clone().unwrap();
+    run_loop(&clock, &db, &list(2, 2), &mut recorder).await;
+    let second_page = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(empty_out, "No sessions yet");
+    assert_eq!(
+        first_page,
+        "id: 3;start: 09:00;end: 17:00\nid: 2;start: 12:00;end: 13:00"
+    );
+    assert_eq!(second_page, "id: 1;start: 09:00;end: 11:00");
+}
+
+#[tokio::test]
+async fn should_not_resume_unpaused_session() {
+    let (clock, mut recorder, db) = setup().await;
+    clock.set(7, 9, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Start {
+            note: None,
+            project: None,
+        },
+        WorktimeCommand::Resume,
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone();
+
+    assert_eq!(last_out, Err("Session isn't paused".into()));
+}
+
+#[tokio::test]
+async fn should_export_sessions_csv() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 2, (9, 12)).await;
+    start_at(&clock, &db, &mut recorder, 3, (9, 0)).await;
+    clock.set(3, 10, 30);
+    let path = env::temp_dir().join(format!("worktime-export-{}.csv", std::process::id()));
+    let std_in: MockStdIn = vec![WorktimeCommand::Export {
+        path: path.clone(),
+        from: chrono::NaiveDate::from_ymd_opt(2025, 7, 2),
+        to: None,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+    let csv = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        last_out,
+        format!("Exported 2 sessions to {}", path.display())
+    );
+    assert_eq!(
+        csv,
+        "id,date,start,end,duration_minutes\n\
+        2,2025-07-02,09:00,12:00,180\n\
+        3,2025-07-03,09:00,,90\n"
+    );
+}
+
+#[tokio::test]
+async fn should_backup_unless_destination_exists() {
+    // `VACUUM INTO` inherits the in-memory flag of the test db, so back a file up instead
+    let (clock, mut recorder, _) = setup().await;
+    let source = env::temp_dir().join(format!("worktime-source-{}.db", std::process::id()));
+    let path = env::temp_dir().join(format!("worktime-backup-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_file(&path);
+    let open = |file: &PathBuf| {
+        SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(file)
+                .create_if_missing(true),
+        )
+    };
+    let pool = open(&source).await.unwrap();
+    MIGRATOR.run(&pool).await.unwrap();
+    let db = WorktimeDatabase::new(pool);
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+    let backup = |force| WorktimeCommand::Backup {
+        path: path.clone(),
+        force,
+    };
+
+    let std_in: MockStdIn = vec![backup(false), backup(false), backup(true)].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let results = recorder.results[recorder.results.len() - 3..].to_vec();
+    // an in-memory db writes no file, so this replacement fails
+    let in_memory = get_test_worktime_db().await.unwrap();
+    let std_in: MockStdIn = vec![backup(true)].into();
+    run_loop(&clock, &in_memory, &std_in, &mut recorder).await;
+    let failed = recorder.results.last().unwrap().clone();
+    let backed_up = WorktimeDatabase::new(open(&path).await.unwrap())
+        .count_sessions()
+        .await
+        .unwrap();
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(results[0].as_ref().unwrap().starts_with("Backed up to"));
+    assert!(matches!(&results[1], Err(err::CommandError::Other(m)) if m.ends_with("use --force")));
+    assert!(results[2].as_ref().unwrap().ends_with(" bytes)"));
+    assert!(failed.is_err());
+    // the previous backup survives the failed one
+    assert_eq!(backed_up, 1);
+}
+
+#[tokio::test]
+async fn should_import_csv_skipping_overlaps() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 2, (9, 12)).await;
+    let path = env::temp_dir().join(format!("worktime-import-{}.csv", std::process::id()));
+    std::fs::write(
+        &path,
+        "id,date,start,end,duration_minutes\n\
+        1,2025-07-01,09:00,12:00,180\n\
+        2,2025-07-02,11:00,13:00,120\n\
+        3,2025-07-03,17:00,25:00,0\n\
+        4,2025-07-03,09:00,10:30,90\n",
+    )
+    .unwrap();
+    let import = |skip_overlaps| WorktimeCommand::Import {
+        path: path.clone(),
+        skip_overlaps,
+    };
+
+    let std_in: MockStdIn = vec![import(false)].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let strict = recorder.results.last().unwrap().clone();
+    let after_strict = db.count_sessions().await.unwrap();
+    let std_in: MockStdIn = vec![import(true)].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let skipping = recorder.results.last().unwrap().clone().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert!(matches!(strict, Err(err::CommandError::Other(m)) if m.starts_with("Line 3 overlaps")));
+    assert_eq!(after_strict, 1);
+    assert_eq!(
+        skipping,
+        "Imported 2, skipped 1 (overlap), 1 malformed\nline 4: invalid time '25:00'"
+    );
+    assert_eq!(db.count_sessions().await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn should_round_trip_export_and_import() {
+    let (clock, mut recorder, db) = setup().await;
+    clock.set(1, 22, 0);
+    db.insert_start(&clock, None, None).await.unwrap();
+    clock.set(2, 2, 0);
+    db.insert_stop(db.get_last_session().await.unwrap().unwrap().id, &clock)
+        .await
+        .unwrap();
+    track(&clock, &db, &mut recorder, 3, (9, 12)).await;
+    let path = env::temp_dir().join(format!("worktime-roundtrip-{}.csv", std::process::id()));
+    let from = chrono::NaiveDate::from_ymd_opt(2025, 7, 1);
+
+    let export = WorktimeCommand::Export {
+        path: path.clone(),
+        from,
+        to: None,
+    };
+    run_loop(&clock, &db, &MockStdIn::from(vec![export]), &mut recorder).await;
+    let imported = get_test_worktime_db().await.unwrap();
+    let import = WorktimeCommand::Import {
+        path: path.clone(),
+        skip_overlaps: false,
+    };
+    run_loop(
+        &clock,
+        &imported,
+        &MockStdIn::from(vec![import]),
+        &mut recorder,
+    )
+    .await;
+    let import_out = recorder.results.last().unwrap().clone().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let spans = |sessions: Vec<WorktimeSession>| -> Vec<_> {
+        sessions.into_iter().map(|s| (s.start, s.end)).collect()
+    };
+    assert_eq!(import_out, "Imported 2, skipped 0 (overlap), 0 malformed");
+    assert_eq!(
+        spans(imported.get_sessions_since(from.unwrap()).await.unwrap()),
+        spans(db.get_sessions_since(from.unwrap()).await.unwrap())
+    );
+    assert_eq!(
+        spans(imported.get_sessions_since(from.unwrap()).await.unwrap())[0],
+        (clock.get(1, 22, 0), Some(clock.get(2, 2, 0)))
+    );
+}
+
+#[tokio::test]
+async fn should_undo_stop_then_start() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 17)).await;
+    let std_in: MockStdIn = vec![WorktimeCommand::Undo].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let reopened_out = recorder.results.last().unwrap().clone().unwrap();
+    let reopened = db.get_last_session().await.unwrap().unwrap();
+    let std_in: MockStdIn = vec![WorktimeCommand::Undo, WorktimeCommand::Undo].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let outs = &recorder.results[recorder.results.len() - 2..];
+
+    assert_eq!(reopened_out, "Reopened session 1 (removed stop at 17:00)");
+    assert_eq!(reopened.end, None);
+    assert_eq!(
+        outs[0].clone().unwrap(),
+        "Removed session 1 (started at 09:00)"
+    );
+    assert_eq!(outs[1], Err("Nothing to undo".into()));
+}
+
+#[tokio::test]
+async fn should_list_session_notes() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    clock.set(7, 12, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Start {
+            note: Some("code review".to_string()),
+            project: None,
+        },
+        WorktimeCommand::Note {
+            nth_last: 1,
+            text: " ".to_string(),
+        },
+        WorktimeCommand::List {
+            limit: 20,
+            offset: 0,
+            tz_shift: None,
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out,
+        "id: 2;start: 12:00;end: -;note: code review\nid: 1;start: 09:00;end: 11:00;note: -"
+    );
+}
+
+#[tokio::test]
+async fn should_reject_stop_before_start() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 8)).await;
+    let last_out = recorder.results.last().unwrap().clone();
+
+    assert_eq!(last_out, Err("End would be before start".into()));
+    assert_eq!(db.get_last_session().await.unwrap().unwrap().end, None);
+}
+
+#[tokio::test]
+async fn should_repair_multiple_running_sessions() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    let first = db.get_last_session().await.unwrap().unwrap();
+    start_at(&clock, &db, &mut recorder, 7, (12, 0)).await;
+    db.reopen_session(first.id, &first.end.unwrap())
+        .await
+        .unwrap();
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Start {
+            note: None,
+            project: None,
+        },
+        WorktimeCommand::Repair { yes: true },
+        WorktimeCommand::Repair { yes: true },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let outs = &recorder.results[recorder.results.len() - 3..];
+    let repaired = db.get_nth_last_session(1).await.unwrap();
+
+    assert_eq!(
+        outs[0],
+        Err("Corrupt data - 2 sessions running! Run `repair`".into())
+    );
+    assert_eq!(
+        outs[1].clone().unwrap(),
+        "Closed 1 stale running session(s) at their start; the latest keeps running"
+    );
+    assert_eq!(outs[2].clone().unwrap(), "Nothing to repair");
+    assert_eq!(repaired.end, Some(repaired.start));
+    assert_eq!(db.get_last_session().await.unwrap().unwrap().end, None);
+}
+
+#[tokio::test]
+async fn should_cancel_running_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    let std_in: MockStdIn = vec![WorktimeCommand::Cancel { yes: true }].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let closed_out = recorder.results.last().unwrap().clone();
+    clock.set(7, 12, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Start {
+            note: None,
+            project: None,
+        },
+        WorktimeCommand::Cancel { yes: true },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(closed_out, Err("No running session to cancel".into()));
+    assert_eq!(last_out, "Cancelled session started at 12:00");
+    assert_eq!(db.count_sessions().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn should_print_detailed_status() {
+    let (clock, mut recorder, db) = setup().await;
+    let detailed = || WorktimeCommand::Status {
+        json: false,
+        watch: false,
+        detailed: true,
+    };
+
+    track(&clock, &db, &mut recorder, 1, (8, 10)).await;
+    clock.set(1, 11, 0);
+    run_loop(
+        &clock,
+        &db,
+        &MockStdIn::from(vec![detailed()]),
+        &mut recorder,
+    )
+    .await;
+    let stopped_out = recorder.results.last().unwrap().clone().unwrap();
+
+    start_at(&clock, &db, &mut recorder, 1, (11, 0)).await;
+    clock.set(1, 13, 30);
+    run_loop(
+        &clock,
+        &db,
+        &MockStdIn::from(vec![detailed()]),
+        &mut recorder,
+    )
+    .await;
+    let running_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(stopped_out, "Not running, last stop at 10:00 (2.00h today)");
+    assert_eq!(
+        running_out,
+        "Running since 11:00 (2.50h elapsed, 4.50h today)"
+    );
+}
+
+#[tokio::test]
+async fn should_add_full_session_without_overlap() {
+    use clap::Parser;
+
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+    let add = |args: [&str; 3]| {
+        cli::Cli::try_parse_from(["worktime", "add", args[0], args[1], args[2]])
+            .unwrap()
+            .command
+    };
+
+    let std_in: MockStdIn = vec![
+        add(["2025-06-30", "09:00", "17:30"]),
+        add(["2025-07-01", "11:00", "13:00"]),
+        add(["2025-07-01", "15:00", "14:00"]),
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let results = &recorder.results[recorder.results.len() - 3..];
+    assert_eq!(
+        results[0],
+        Ok("Added session on 2025-06-30 from 09:00 to 17:30".to_string())
+    );
+    assert!(
+        matches!(&results[1], Err(err::CommandError::Other(m)) if m.starts_with("Overlaps session"))
+    );
+    assert!(
+        matches!(&results[2], Err(err::CommandError::Other(m)) if m == "End would be before start")
+    );
+    assert_eq!(db.count_sessions().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn should_continue_only_within_grace_window() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+
+    clock.set(1, 12, 20);
+    let std_in: MockStdIn = vec![WorktimeCommand::Continue].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let within = recorder.results.last().unwrap().clone();
+    let reopened = db.get_last_session().await.unwrap().unwrap();
+
+    clock.set(1, 14, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+        threshold_alert: None,
+        trim_idle: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    clock.set(1, 15, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Continue].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let outside = recorder.results.last().unwrap().clone();
+
+    assert_eq!(
+        within.unwrap(),
+        "Continue session started at 09:00 (stopped at 12:00)"
+    );
+    assert_eq!(reopened.end, None);
+    assert!(
+        matches!(outside, Err(err::CommandError::Other(m)) if m.ends_with("use `start` instead"))
+    );
+    assert_eq!(db.count_sessions().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn should_reject_correct_out_of_range_on_empty_db() {
+    let (clock, mut recorder, db) = setup().await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Correct {
+        nth_last: 0,
+        kind: cli::CorrectionKind::End,
+        hours: 17,
+        minutes: 0,
+        relative: false,
+        date: None,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    assert!(matches!(
+        recorder.results.last().unwrap(),
+        Err(err::CommandError::Other(m)) if m == "No session at position 0; only 0 sessions exist"
+    ));
+}
+
+#[tokio::test]
+async fn should_abort_unconfirmed_delete() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+
+    let delete = WorktimeCommand::Delete {
+        nth_last: 0,
+        yes: false,
+    };
+    let prompt = delete.confirmation(&db).await;
+    run_loop(&clock, &db, &MockStdIn::from(vec![delete]), &mut recorder).await;
+
+    assert_eq!(
+        prompt.unwrap(),
+        "Delete session 'id: 1;start: 09:00;end: 12:00'?"
+    );
+    assert!(matches!(
+        recorder.results.last().unwrap(),
+        Err(err::CommandError::Other(m)) if m == "Aborted"
+    ));
+    assert_eq!(db.count_sessions().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn should_print_flex_balance_since_first_session() {
+    let (clock, mut recorder, db) = setup().await;
+    // Fri 4th 9h, Mon 7th 8h, Tue 8th 6.5h (so far)
+    track(&clock, &db, &mut recorder, 4, (8, 17)).await;
+    track(&clock, &db, &mut recorder, 7, (8, 16)).await;
+    start_at(&clock, &db, &mut recorder, 8, (8, 0)).await;
+
+    clock.set(8, 14, 30);
+    let std_in: MockStdIn = vec![WorktimeCommand::Balance { since: None }].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    assert_eq!(
+        recorder.results.last().unwrap().clone().unwrap(),
+        "Flex balance since 2025-07-04: -0.50h"
+    );
+}
+
+#[tokio::test]
+async fn should_tick_until_session_stops() {
+    let (clock, mut recorder, db) = setup().await;
+    let watch = WorktimeCommand::Watch;
+    let idle = watch.watch_tick(&db, &clock).await.unwrap();
+
+    start_at(&clock, &db, &mut recorder, 1, (9, 0)).await;
+    clock.set(1, 11, 3);
+    let running = watch.watch_tick(&db, &clock).await.unwrap();
+    let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+        threshold_alert: None,
+        trim_idle: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let stopped = watch.watch_tick(&db, &clock).await.unwrap();
+
+    assert_eq!(idle, None);
+    assert_eq!(running.as_deref(), Some("Elapsed: 02:03:00"));
+    assert_eq!(stopped, None);
+}
+
+#[tokio::test]
+async fn should_leave_db_unchanged_on_dry_run() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 17)).await;
+    let before = db.get_last_session().await.unwrap().unwrap();
+
+    let correct = WorktimeCommand::Correct {
+        nth_last: 0,
+        kind: cli::CorrectionKind::Start,
+        hours: 7,
+        minutes: 30,
+        relative: false,
+        date: None,
+    };
+    let preview = correct.execute(&db, &clock, true).await.unwrap();
+    assert_eq!(
+        preview.message,
+        format!("Would update start time of '{}' to '07:30'", before.id)
+    );
+    let delete = WorktimeCommand::Delete {
+        nth_last: 0,
+        yes: false,
+    };
+    assert!(delete.execute(&db, &clock, true).await.is_ok());
+    let start = WorktimeCommand::Start {
+        note: None,
+        project: None,
+    };
+    assert!(matches!(
+        start.execute(&db, &clock, true).await,
+        Err(err::CommandError::Other(m)) if m == "Start doesn't support --dry-run"
+    ));
+
+    assert_eq!(db.get_last_session().await.unwrap(), Some(before));
+    assert_eq!(db.count_sessions().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn should_move_session_with_its_breaks_to_another_date() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 17)).await;
+    start_at(&clock, &db, &mut recorder, 9, (9, 0)).await;
+    clock.set(9, 12, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Pause].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let id = db.get_last_session().await.unwrap().unwrap().id;
+
+    let correct = |date| WorktimeCommand::Correct {
+        nth_last: 0,
+        kind: cli::CorrectionKind::Start,
+        hours: 9,
+        minutes: 30,
+        relative: false,
+        date: Some(date),
+    };
+    assert!(matches!(
+        correct(clock.get(7, 0, 0).date()).execute(&db, &clock, false).await,
+        Err(err::CommandError::Other(m)) if m.starts_with("Would overlap session")
+    ));
+    correct(clock.get(8, 0, 0).date())
+        .execute(&db, &clock, false)
+        .await
+        .unwrap();
+
+    let moved = db.get_last_session().await.unwrap().unwrap();
+    assert_eq!(moved.start, clock.get(8, 9, 30));
+    let pause = db.get_open_break(id).await.unwrap().unwrap();
+    assert_eq!(pause.start, clock.get(8, 12, 0));
+}
+
+#[tokio::test]
+async fn should_reject_corrections_overlapping_another_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 12)).await;
+    track(&clock, &db, &mut recorder, 7, (13, 17)).await;
+    let correct = |relative, hours| WorktimeCommand::Correct {
+        nth_last: 0,
+        kind: cli::CorrectionKind::Start,
+        hours,
+        minutes: 0,
+        relative,
+        date: None,
+    };
+
+    for (relative, hours) in [(true, -2), (false, 11)] {
+        assert!(matches!(
+            correct(relative, hours).execute(&db, &clock, false).await,
+            Err(err::CommandError::Other(m)) if m.starts_with("Would overlap session")
+        ));
+    }
+    correct(true, -1).execute(&db, &clock, false).await.unwrap();
+
+    let last = db.get_last_session().await.unwrap().unwrap();
+    assert_eq!(last.start, clock.get(7, 12, 0));
+}
+
+#[tokio::test]
+async fn should_exit_after_one_plain_command_with_its_status() {
+    let (clock, mut recorder, db) = setup().await;
+    let plain = |commands| MockStdIn::from(commands).with_format(ResultFormat::Plain);
+
+    let failed = run_loop(
+        &clock,
+        &db,
+        &plain(vec![WorktimeCommand::Undo]),
+        &mut recorder,
+    )
+    .await;
+    let std_in = plain(vec![
+        WorktimeCommand::Start {
+            note: None,
+            project: None,
+        },
+        WorktimeCommand::Undo,
+    ]);
+    let started = run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    assert_eq!(failed, ExitCode::FAILURE);
+    assert_eq!(started, ExitCode::SUCCESS);
+    assert_eq!(recorder.results.len(), 2);
+    assert!(db.get_last_session().await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn should_exit_with_the_status_of_the_given_command() {
+    let (clock, mut recorder, db) = setup().await;
+    let start = || WorktimeCommand::Start {
+        note: None,
+        project: None,
+    };
+
+    let std_in = MockStdIn::from(vec![WorktimeCommand::Undo, start()]);
+    let failed = run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let stop = || WorktimeCommand::Stop {
+        threshold_alert: None,
+        trim_idle: false,
+    };
+    let std_in = MockStdIn::from(vec![stop(), stop()]);
+    let stopped = run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    assert_eq!(failed, ExitCode::FAILURE);
+    assert_eq!(stopped, ExitCode::SUCCESS);
+    // the second stop ran interactively
+    assert!(recorder.results.last().unwrap().is_err());
+}
+
+#[tokio::test]
+async fn should_generate_completions() {
+    let (clock, mut recorder, db) = setup().await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Completions {
+        shell: clap_complete::Shell::Bash,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert!(!last_out.is_empty());
+    assert!(last_out.contains("worktime"));
+}
+
+#[tokio::test]
+async fn should_correct() {
+    let (clock, mut recorder, db) = setup().await;
+
+    for day_offset in 0..5 {
+        track(&clock, &db, &mut recorder, 7 + day_offset, (9, 17)).await;
+    }
+
+    let id_of_wednesday = db.get_nth_last_session(2).await.unwrap().id;
+
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Correct {
+            nth_last: 2,
+            kind: cli::CorrectionKind::Start,
+            hours: 7,
+            minutes: 30,
+            relative: false,
+            date: None,
+        },
+        WorktimeCommand::Correct {
+            nth_last: 2,
+            kind: cli::CorrectionKind::End,
+            hours: 15,
+            minutes: 30,
+            relative: false,
+            date: None,
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let wednesday_corrected = db.get_session_by_id(id_of_wednesday).await.unwrap();
+    let expected = WorktimeSession::new(
+        id_of_wednesday,
+        clock.get(9, 7, 30),
+        Some(clock.get(9, 15, 30)),
+    );
+
+    assert_eq!(expected, wednesday_corrected);
+}
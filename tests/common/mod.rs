@@ -0,0 +1,54 @@
+//! helpers shared by the command tests
+
+use worktime::{
+    cli::WorktimeCommand, db::WorktimeDatabase, db::get_test_worktime_db, run_loop,
+    stdin::test_utils::MockStdIn, stdout::test_utils::StdOutRecorder, time::test_utils::MockClock,
+};
+
+pub async fn setup() -> (MockClock, StdOutRecorder, WorktimeDatabase) {
+    let clock = MockClock::default();
+    let db = get_test_worktime_db().await.unwrap();
+    (clock, StdOutRecorder::default(), db)
+}
+
+/// starts a session on July `day` at `hour`:`minute` and leaves it running
+pub async fn start_at(
+    clock: &MockClock,
+    db: &WorktimeDatabase,
+    recorder: &mut StdOutRecorder,
+    day: u32,
+    (hour, minute): (u32, u32),
+) {
+    clock.set(day, hour, minute);
+    let std_in: MockStdIn = vec![WorktimeCommand::Start {
+        note: None,
+        project: None,
+    }]
+    .into();
+    run_loop(clock, db, &std_in, recorder).await;
+}
+
+/// tracks a closed session on July `day` from `start` to `end` (hours)
+pub async fn track(
+    clock: &MockClock,
+    db: &WorktimeDatabase,
+    recorder: &mut StdOutRecorder,
+    day: u32,
+    (start, end): (u32, u32),
+) {
+    start_at(clock, db, recorder, day, (start, 0)).await;
+    clock.set(day, end, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+        threshold_alert: None,
+        trim_idle: false,
+    }]
+    .into();
+    run_loop(clock, db, &std_in, recorder).await;
+}
+
+pub fn assert_ends_with(actual: &str, expected_end: &str) {
+    assert!(
+        actual.ends_with(expected_end),
+        "expected '{actual}' to end with '{expected_end}'"
+    );
+}
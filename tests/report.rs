@@ -0,0 +1,1413 @@
+mod common;
+
+use common::*;
+use std::env;
+use worktime::{
+    cli::{self, ReportKind, ReportOptions, WorktimeCommand},
+    db::{self},
+    err, format, run_loop,
+    stdin::test_utils::MockStdIn,
+    stdout::{self, ResultFormat},
+};
+
+#[tokio::test]
+async fn should_record_workday() {
+    let (clock, mut recorder, db) = setup().await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Start {
+        note: None,
+        project: None,
+    }]
+    .into();
+    clock.set(1, 9, 00);
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Stop {
+            threshold_alert: None,
+            trim_idle: false,
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions::default(),
+        },
+    ]
+    .into();
+    clock.set(1, 15, 00);
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(last_out.as_str(), "6.00h (-2.00h vs target)");
+}
+
+#[tokio::test]
+async fn should_record_workweek() {
+    let (clock, mut recorder, db) = setup().await;
+
+    for day_offset in 0..5 {
+        track(&clock, &db, &mut recorder, 7 + day_offset, (9, 17)).await;
+    }
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions::default(),
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out,
+        "Week's balance: 40.00h (+0.00h vs target)\n\
+         Mon 8.00h / Tue 8.00h / Wed 8.00h / Thu 8.00h / Fri 8.00h / Sat 0.00h / Sun 0.00h / \
+         Total 40.00h"
+    );
+}
+
+#[tokio::test]
+async fn should_leave_holidays_out_of_the_working_day_average() {
+    use cli::HolidayAction;
+
+    let (clock, mut recorder, db) = setup().await;
+    for day in 8..=11 {
+        track(&clock, &db, &mut recorder, day, (8, 16)).await;
+    }
+
+    clock.set(11, 18, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Add {
+                date: clock.get(7, 0, 0).date(),
+                kind: db::HolidayKind::Public,
+            },
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Week,
+            options: ReportOptions {
+                working_days_only: true,
+                ..Default::default()
+            },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(last_out.as_str(), "avg/day: 8.00h over 4 working days");
+}
+
+#[tokio::test]
+async fn should_name_breakdown_days_in_locale() {
+    use clap::Parser;
+
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (8, 16)).await;
+    let report = |locale: &str| {
+        cli::Cli::try_parse_from(["worktime", "report", "week", "--locale", locale])
+            .map(|cli| cli.command)
+    };
+
+    clock.set(7, 18, 0);
+    run_loop(
+        &clock,
+        &db,
+        &MockStdIn::from(vec![report("de_DE").unwrap()]),
+        &mut recorder,
+    )
+    .await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert!(
+        last_out
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("Mo 8.00h / Di 0.00h / Mi 0.00h"),
+        "{last_out}"
+    );
+    assert!(report("xx_XX").is_err());
+}
+
+#[tokio::test]
+async fn should_expect_the_schedule_without_a_mid_week_holiday() {
+    use cli::HolidayAction;
+
+    let (clock, mut recorder, db) = setup().await;
+    for day in [7, 8, 10, 11] {
+        track(&clock, &db, &mut recorder, day, (8, 17)).await;
+    }
+
+    clock.set(11, 18, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Add {
+                date: clock.get(9, 0, 0).date(),
+                kind: db::HolidayKind::Public,
+            },
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Week,
+            options: ReportOptions {
+                target_from_schedule: true,
+                balance_only: true,
+                ..Default::default()
+            },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    // the default schedule: 8h Mon-Fri, so 32h expected around Wednesday
+    assert_eq!(last_out, "+4.00h");
+}
+
+#[tokio::test]
+async fn should_annotate_holidays_in_the_breakdown() {
+    use cli::HolidayAction;
+
+    let (clock, mut recorder, db) = setup().await;
+    for day in 8..=11 {
+        track(&clock, &db, &mut recorder, day, (8, 16)).await;
+    }
+
+    clock.set(11, 18, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Add {
+                date: clock.get(7, 0, 0).date(),
+                kind: db::HolidayKind::Public,
+            },
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Week,
+            options: ReportOptions {
+                annotate_holidays: true,
+                working_days_only: true,
+                ..Default::default()
+            },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out.lines().collect::<Vec<_>>(),
+        vec![
+            "Week's balance: 32.00h (+0.00h vs target)",
+            "1 holiday(s) included",
+            "Mon 0.00h (holiday) / Tue 8.00h / Wed 8.00h / Thu 8.00h / Fri 8.00h / \
+             Sat 0.00h / Sun 0.00h / Total 32.00h",
+            "avg/day: 8.00h over 4 working days",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_average_over_working_days() {
+    let (clock, mut recorder, db) = setup().await;
+
+    // Mon - Thu tracked, Fri untracked
+    for day_offset in 0..4 {
+        track(&clock, &db, &mut recorder, 7 + day_offset, (9, 17)).await;
+    }
+
+    clock.set(11, 18, 00); // Friday
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            working_days_only: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(last_out.as_str(), "avg/day: 6.40h over 5 working days");
+}
+
+#[tokio::test]
+async fn should_flag_sessions_over_threshold() {
+    let (clock, mut recorder, db) = setup().await;
+
+    start_at(&clock, &db, &mut recorder, 1, (8, 0)).await;
+    clock.set(1, 13, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+        threshold_alert: Some(4 * 60),
+        trim_idle: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let stop_out = recorder.results.last().unwrap().clone().unwrap();
+
+    start_at(&clock, &db, &mut recorder, 1, (14, 0)).await;
+    clock.set(1, 15, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            threshold_alert: Some(4 * 60),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let report_out = recorder.results.last().unwrap().clone().unwrap();
+
+    let id = db.get_nth_last_session(1).await.unwrap().id;
+    let expected = format!("Warning: session '{id}' ran 5.00h (threshold 4.00h)");
+    assert_ends_with(stop_out.as_str(), &expected);
+    assert_ends_with(report_out.as_str(), &expected);
+    assert_eq!(1, report_out.matches("Warning").count());
+}
+
+#[tokio::test]
+async fn should_keep_breaks_when_merging_a_day() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (8, 10)).await;
+    for (hour, minute, command) in [
+        (
+            11,
+            0,
+            WorktimeCommand::Start {
+                note: None,
+                project: None,
+            },
+        ),
+        (11, 15, WorktimeCommand::Pause),
+        (11, 45, WorktimeCommand::Resume),
+        (
+            12,
+            0,
+            WorktimeCommand::Stop {
+                threshold_alert: None,
+                trim_idle: false,
+            },
+        ),
+    ] {
+        clock.set(1, hour, minute);
+        run_loop(&clock, &db, &MockStdIn::from(vec![command]), &mut recorder).await;
+    }
+    track(&clock, &db, &mut recorder, 1, (13, 17)).await;
+    let first_id = db.get_nth_last_session(2).await.unwrap().id;
+
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Merge {
+            date: Some(clock.get(1, 0, 0).date()),
+            first_nth: None,
+            second_nth: None,
+            preserve_total: true,
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                anchor: Some(clock.get(1, 0, 0).date()),
+                ..Default::default()
+            },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+
+    let merged = db
+        .get_sessions_since(clock.get(1, 0, 0).date())
+        .await
+        .unwrap();
+    assert_eq!(1, merged.len());
+    assert_eq!(first_id, merged[0].id);
+    assert_eq!(Some(clock.get(1, 15, 0)), merged[0].end);
+    assert_eq!(1, merged[0].breaks.len());
+    let report = recorder.results.last().unwrap().clone().unwrap();
+    assert!(report.starts_with("Day's balance: 6.50h"), "{report}");
+}
+
+#[tokio::test]
+async fn should_append_reports_to_file() {
+    let (clock, mut recorder, db) = setup().await;
+    let path = env::temp_dir().join(format!("worktime-append-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    start_at(&clock, &db, &mut recorder, 1, (9, 0)).await;
+
+    for hour in [11, 12] {
+        clock.set(1, hour, 00);
+        let std_in: MockStdIn = vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                output: Some(path.clone()),
+                append: true,
+                ..Default::default()
+            },
+        }]
+        .into();
+        run_loop(&clock, &db, &std_in, &mut recorder).await;
+    }
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(
+        written,
+        "# 2025-07-01 11:00\nDay's balance: 2.00h (-6.00h vs target)\n# 2025-07-01 12:00\nDay's balance: 3.00h (-5.00h vs target)\n"
+    );
+}
+
+#[tokio::test]
+async fn should_print_weekly_target_grid() {
+    let (clock, mut recorder, db) = setup().await;
+
+    // 2 days in W27 (starting Tue 1st), full W28, 10h in W29
+    let days = [
+        (2, 17),
+        (3, 17),
+        (7, 17),
+        (8, 17),
+        (9, 17),
+        (10, 17),
+        (11, 17),
+    ];
+    for (day, end) in days.into_iter().chain([(14, 19)]) {
+        track(&clock, &db, &mut recorder, day, (9, end)).await;
+    }
+
+    clock.set(20, 12, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Month,
+        options: ReportOptions {
+            weekly_target_grid: Some(40f64),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+    let rows: Vec<&str> = last_out.lines().skip(3).collect();
+
+    assert_eq!(
+        rows,
+        vec![
+            "2025-W27     16.00h   32.00h  -16.00h",
+            "2025-W28     40.00h   40.00h   +0.00h",
+            "2025-W29     10.00h   40.00h  -30.00h",
+            "total        66.00h  112.00h  -46.00h",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_print_target_trend() {
+    let (clock, mut recorder, db) = setup().await;
+
+    // W28 6h/day, W29 7h/day, W30 8h/day
+    for (week_offset, hours) in [(0, 6), (1, 7), (2, 8)] {
+        for day_offset in 0..5 {
+            let day = 7 + week_offset * 7 + day_offset; // 7 = Monday
+            track(&clock, &db, &mut recorder, day, (9, 9 + hours)).await;
+        }
+    }
+
+    clock.set(27, 12, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Month,
+        options: ReportOptions {
+            compare_target_trend: Some(40f64),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+    let rows: Vec<&str> = last_out.lines().skip(2).collect();
+
+    assert_eq!(
+        rows,
+        vec![
+            "2025-W27    -32.00h",
+            "2025-W28    -10.00h ↑",
+            "2025-W29     -5.00h ↑",
+            "2025-W30     +0.00h ↑",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_exclude_sessions_from_report() {
+    let (clock, mut recorder, db) = setup().await;
+
+    for (start, end) in [(8, 12), (13, 14), (15, 17)] {
+        track(&clock, &db, &mut recorder, 1, (start, end)).await;
+    }
+    let bogus = db.get_nth_last_session(1).await.unwrap().id;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            exclude_sessions: vec![bogus.into()],
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(last_out.as_str(), "6.00h (-2.00h vs target)");
+}
+
+#[tokio::test]
+async fn should_report_prometheus_metric() {
+    let (clock, mut recorder, db) = setup().await;
+
+    start_at(&clock, &db, &mut recorder, 7, (9, 0)).await;
+    clock.set(8, 1, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            output_format: format::OutputFormat::Prometheus,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out.lines().last().unwrap(),
+        r#"worktime_tracked_seconds{period="week"} 57600"#
+    );
+}
+
+#[tokio::test]
+async fn should_show_percentage_of_workweek() {
+    let (clock, mut recorder, db) = setup().await;
+
+    for day_offset in 0..5 {
+        track(&clock, &db, &mut recorder, 7 + day_offset, (9, 15)).await;
+    }
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            workweek_hours: Some(40f64),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(
+        last_out.lines().next().unwrap(),
+        "30.00h (-10.00h vs target) (75% of 40h)",
+    );
+}
+
+#[tokio::test]
+async fn should_omit_zero_total() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+
+    let report = |day| {
+        clock.set(day, 18, 00);
+        let std_in: MockStdIn = vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                omit_empty_total_zero: true,
+                ..Default::default()
+            },
+        }]
+        .into();
+        std_in
+    };
+    run_loop(&clock, &db, &report(1), &mut recorder).await;
+    let tracked_out = recorder.results.last().unwrap().clone().unwrap();
+    run_loop(&clock, &db, &report(2), &mut recorder).await;
+    let empty_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(tracked_out.as_str(), "3.00h (-5.00h vs target)");
+    assert_eq!(empty_out, "");
+}
+
+#[tokio::test]
+async fn should_print_nothing_plain_for_omitted_zero_total() {
+    let (clock, _, db) = setup().await;
+    let path = env::temp_dir().join(format!("worktime-omit-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let std_in = MockStdIn::from(vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            omit_empty_total_zero: true,
+            ..Default::default()
+        },
+    }])
+    .with_format(ResultFormat::Plain);
+
+    let mut std_out = stdout::get_std_out(Some(&path), false).unwrap();
+    run_loop(&clock, &db, &std_in, &mut std_out).await;
+    let printed = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(printed, "");
+}
+
+#[tokio::test]
+async fn should_flag_long_gaps() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (8, 10)).await;
+    track(&clock, &db, &mut recorder, 1, (12, 13)).await;
+    track(&clock, &db, &mut recorder, 1, (14, 17)).await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            max_gap_alert: Some(90),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    let before = db.get_nth_last_session(2).await.unwrap().id;
+    let after = db.get_nth_last_session(1).await.unwrap().id;
+    assert_eq!(1, last_out.matches("Warning").count());
+    assert_ends_with(
+        last_out.as_str(),
+        &format!("Warning: 2.00h gap between session '{before}' and '{after}' (threshold 1.50h)"),
+    );
+}
+
+#[tokio::test]
+async fn should_print_weekly_rollup_csv() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 8, (9, 13)).await;
+    track(&clock, &db, &mut recorder, 9, (9, 13)).await;
+
+    clock.set(20, 12, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Month,
+        options: ReportOptions {
+            weekly_rollup_csv: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out,
+        "iso_week,year,hours\n27,2025,8.00\n28,2025,8.00\n29,2025,0.00"
+    );
+}
+
+#[tokio::test]
+async fn should_sum_weekly_overtime_only() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 7, (8, 21)).await;
+    track(&clock, &db, &mut recorder, 14, (9, 17)).await;
+
+    clock.set(20, 12, 00);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Month,
+        options: ReportOptions {
+            weekly_overtime_only: Some(10f64),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(last_out, "Weekly overtime: 3.00h");
+}
+
+#[tokio::test]
+async fn should_report_lifetime_total() {
+    let (clock, mut recorder, db) = setup().await;
+    let lifetime = || -> MockStdIn {
+        vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                since_first: true,
+                ..Default::default()
+            },
+        }]
+        .into()
+    };
+
+    run_loop(&clock, &db, &lifetime(), &mut recorder).await;
+    let empty_out = recorder.results.last().unwrap().clone().unwrap();
+
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 15, (9, 12)).await;
+    track(&clock, &db, &mut recorder, 28, (8, 10)).await;
+    run_loop(&clock, &db, &lifetime(), &mut recorder).await;
+    let lifetime_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(empty_out, "Lifetime balance: 0.00h (-8.00h vs target)");
+    assert_eq!(
+        lifetime_out,
+        "Lifetime balance: 13.00h (-147.00h vs target)"
+    );
+}
+
+#[tokio::test]
+async fn should_round_each_day() {
+    let (clock, mut recorder, db) = setup().await;
+    for day in [7, 8] {
+        start_at(&clock, &db, &mut recorder, day, (9, 0)).await;
+        clock.set(day, 16, 7);
+        let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+            threshold_alert: None,
+            trim_idle: false,
+        }]
+        .into();
+        run_loop(&clock, &db, &std_in, &mut recorder).await;
+    }
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            round_each_day: Some(15),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(
+        last_out.lines().next().unwrap(),
+        "14.50h (-25.50h vs target)",
+    );
+}
+
+#[tokio::test]
+async fn should_print_bare_balance() {
+    let (clock, mut recorder, db) = setup().await;
+    start_at(&clock, &db, &mut recorder, 1, (8, 0)).await;
+
+    clock.set(1, 17, 30);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            balance_only: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(last_out, "+1.50h");
+}
+
+#[tokio::test]
+async fn should_mask_weekdays() {
+    use chrono::Weekday::*;
+
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 17)).await; // Monday
+    track(&clock, &db, &mut recorder, 8, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 12, (10, 13)).await; // Saturday
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            weekday_mask: vec![Mon, Tue, Wed, Thu, Fri],
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_ends_with(
+        last_out.lines().next().unwrap(),
+        "16.00h (-24.00h vs target)",
+    );
+}
+
+#[test]
+fn should_parse_weekday_mask() {
+    use clap::Parser;
+
+    let cli = cli::Cli::try_parse_from(["worktime", "report", "week", "--weekday-mask", "mon,fri"])
+        .unwrap();
+    let WorktimeCommand::Report { options, .. } = cli.command else {
+        panic!("expected report");
+    };
+    assert_eq!(
+        options.weekday_mask,
+        vec![chrono::Weekday::Mon, chrono::Weekday::Fri]
+    );
+}
+
+#[tokio::test]
+async fn should_normalize_open_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (8, 12)).await;
+    start_at(&clock, &db, &mut recorder, 1, (13, 0)).await;
+
+    let mut outs = Vec::new();
+    for hour in [14, 18] {
+        clock.set(1, hour, 00);
+        let std_in: MockStdIn = vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                normalize_open: true,
+                ..Default::default()
+            },
+        }]
+        .into();
+        run_loop(&clock, &db, &std_in, &mut recorder).await;
+        outs.push(recorder.results.last().unwrap().clone().unwrap());
+    }
+
+    assert_eq!(outs[0], outs[1]);
+    assert_ends_with(outs[0].as_str(), "4.00h (-4.00h vs target)");
+}
+
+#[tokio::test]
+async fn should_format_decimal_comma() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 15)).await;
+
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            decimal_comma: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(last_out, "Day's balance: 6,00h (-2,00h vs target)");
+}
+
+#[tokio::test]
+async fn should_filter_short_sessions() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+    start_at(&clock, &db, &mut recorder, 1, (13, 0)).await;
+    *clock.mock_time.borrow_mut() = clock.get(1, 13, 0) + chrono::TimeDelta::seconds(30);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Stop {
+            threshold_alert: None,
+            trim_idle: false,
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                min_session: Some(1),
+                ..Default::default()
+            },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out,
+        "Day's balance: 3.00h (-5.00h vs target)\n1 session(s) shorter than 1min filtered"
+    );
+}
+
+#[tokio::test]
+async fn should_report_week_around_anchor() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 4, (9, 10)).await;
+    track(&clock, &db, &mut recorder, 8, (9, 11)).await;
+    track(&clock, &db, &mut recorder, 13, (9, 12)).await;
+    track(&clock, &db, &mut recorder, 15, (9, 13)).await;
+    clock.set(20, 12, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            anchor: chrono::NaiveDate::from_ymd_opt(2025, 7, 9),
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out.lines().next().unwrap(),
+        "Week's balance: 5.00h (-35.00h vs target)"
+    );
+    assert_ends_with(&last_out, "Sun 3.00h / Total 5.00h");
+}
+
+#[tokio::test]
+async fn should_mark_running_session() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 7, (9, 11)).await;
+    start_at(&clock, &db, &mut recorder, 7, (12, 0)).await;
+    clock.set(7, 13, 30);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            include_running_marker: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out,
+        "Day's balance: 3.50h (-4.50h vs target)\n\
+        id: 1;start: 09:00;end: 11:00\n\
+        id: 2;start: 12:00;end: - ⏳ running 01:30:00"
+    );
+}
+
+#[tokio::test]
+async fn should_expect_no_target_on_weekends() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 4, (9, 17)).await; // Friday
+    track(&clock, &db, &mut recorder, 6, (10, 12)).await; // Sunday
+    let report = |kind| -> MockStdIn {
+        vec![WorktimeCommand::Report {
+            kind,
+            options: ReportOptions::default(),
+        }]
+        .into()
+    };
+
+    run_loop(&clock, &db, &report(ReportKind::Day), &mut recorder).await;
+    let day_out = recorder.results.last().unwrap().clone().unwrap();
+    run_loop(&clock, &db, &report(ReportKind::Month), &mut recorder).await;
+    let month_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(day_out, "Day's balance: 2.00h (+2.00h vs target)");
+    assert_eq!(
+        month_out,
+        "Month's balance: 10.00h (-22.00h vs target)\ndays worked: 2, avg/day: 5.00h"
+    );
+}
+
+#[tokio::test]
+async fn should_attribute_overnight_session_to_both_days() {
+    let (clock, mut recorder, db) = setup().await;
+    start_at(&clock, &db, &mut recorder, 7, (23, 30)).await;
+    clock.set(8, 0, 30);
+    let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+        threshold_alert: None,
+        trim_idle: false,
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let report = |anchor| -> MockStdIn {
+        vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                anchor,
+                ..Default::default()
+            },
+        }]
+        .into()
+    };
+
+    run_loop(&clock, &db, &report(None), &mut recorder).await;
+    let today_out = recorder.results.last().unwrap().clone().unwrap();
+    let yesterday = chrono::NaiveDate::from_ymd_opt(2025, 7, 7);
+    run_loop(&clock, &db, &report(yesterday), &mut recorder).await;
+    let yesterday_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(today_out, "Day's balance: 0.50h (-7.50h vs target)");
+    assert_eq!(yesterday_out, "Day's balance: 0.50h (-7.50h vs target)");
+}
+
+#[tokio::test]
+async fn should_subtract_breaks() {
+    let (clock, mut recorder, db) = setup().await;
+    start_at(&clock, &db, &mut recorder, 7, (9, 0)).await;
+    clock.set(7, 12, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Pause].into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    clock.set(7, 12, 45);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Status {
+            json: false,
+            watch: false,
+            detailed: false,
+        },
+        WorktimeCommand::Resume,
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let paused_out = recorder.results[recorder.results.len() - 2]
+        .clone()
+        .unwrap();
+    clock.set(7, 17, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Stop {
+            threshold_alert: None,
+            trim_idle: false,
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions::default(),
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(paused_out, "Paused since 12:00");
+    assert_eq!(last_out, "Day's balance: 7.25h (-0.75h vs target)");
+}
+
+#[tokio::test]
+async fn should_report_date_range() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 2, (9, 12)).await;
+    track(&clock, &db, &mut recorder, 3, (9, 10)).await;
+    clock.set(10, 12, 0);
+    let report = |since, until| -> MockStdIn {
+        vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                since,
+                until,
+                ..Default::default()
+            },
+        }]
+        .into()
+    };
+    let date = |day| chrono::NaiveDate::from_ymd_opt(2025, 7, day);
+
+    run_loop(&clock, &db, &report(date(2), date(3)), &mut recorder).await;
+    let range_out = recorder.results.last().unwrap().clone().unwrap();
+    run_loop(&clock, &db, &report(None, date(2)), &mut recorder).await;
+    let until_out = recorder.results.last().unwrap().clone().unwrap();
+    run_loop(&clock, &db, &report(date(3), None), &mut recorder).await;
+    let since_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        range_out,
+        "2025-07-02 to 2025-07-03 balance: 4.00h (-12.00h vs target)"
+    );
+    assert_eq!(
+        until_out,
+        "2025-07-01 to 2025-07-02 balance: 11.00h (-5.00h vs target)"
+    );
+    assert_eq!(
+        since_out,
+        "2025-07-03 to 2025-07-10 balance: 1.00h (-47.00h vs target)"
+    );
+}
+
+#[tokio::test]
+async fn should_detail_gross_breaks_and_net_per_day() {
+    let (clock, mut recorder, db) = setup().await;
+    for (hour, command) in [
+        (
+            9,
+            WorktimeCommand::Start {
+                note: None,
+                project: None,
+            },
+        ),
+        (12, WorktimeCommand::Pause),
+        (13, WorktimeCommand::Resume),
+        (
+            17,
+            WorktimeCommand::Stop {
+                threshold_alert: None,
+                trim_idle: false,
+            },
+        ),
+    ] {
+        clock.set(7, hour, 0);
+        run_loop(&clock, &db, &MockStdIn::from(vec![command]), &mut recorder).await;
+    }
+
+    clock.set(7, 18, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            net_of_breaks_detail: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out.lines().skip(1).collect::<Vec<_>>(),
+        vec![
+            "date        gross  breaks    net",
+            "2025-07-07  8.00h   1.00h  7.00h",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_summarize_notes_beneath_the_total() {
+    let (clock, mut recorder, db) = setup().await;
+    for hours in [(8, 10), (10, 11), (11, 12)] {
+        track(&clock, &db, &mut recorder, 7, hours).await;
+    }
+    let long = "a".repeat(70);
+
+    clock.set(7, 18, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Note {
+            nth_last: 0,
+            text: long.clone(),
+        },
+        WorktimeCommand::Note {
+            nth_last: 2,
+            text: "standup".to_string(),
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                include_note_summary: true,
+                ..Default::default()
+            },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        last_out.lines().collect::<Vec<_>>(),
+        vec![
+            "Day's balance: 4.00h (-4.00h vs target)",
+            "- standup",
+            &format!("- {}…", &long[..59]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_report_per_project() {
+    use clap::Parser;
+
+    let (clock, mut recorder, db) = setup().await;
+    for (project, (start, end)) in [
+        (Some("acme"), (8, 12)),
+        (Some("beta"), (13, 15)),
+        (Some("acme"), (15, 17)),
+        (None, (17, 18)),
+    ] {
+        clock.set(7, start, 0);
+        let std_in: MockStdIn = vec![WorktimeCommand::Start {
+            note: None,
+            project: project.map(str::to_string),
+        }]
+        .into();
+        run_loop(&clock, &db, &std_in, &mut recorder).await;
+        clock.set(7, end, 0);
+        let std_in: MockStdIn = vec![WorktimeCommand::Stop {
+            threshold_alert: None,
+            trim_idle: false,
+        }]
+        .into();
+        run_loop(&clock, &db, &std_in, &mut recorder).await;
+    }
+    let report = |options| -> MockStdIn {
+        vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options,
+        }]
+        .into()
+    };
+
+    let by_project = ReportOptions {
+        by_project: true,
+        ..Default::default()
+    };
+    run_loop(&clock, &db, &report(by_project), &mut recorder).await;
+    let grouped_out = recorder.results.last().unwrap().clone().unwrap();
+    let acme = ReportOptions {
+        project: Some("acme".to_string()),
+        ..Default::default()
+    };
+    run_loop(&clock, &db, &report(acme), &mut recorder).await;
+    let acme_out = recorder.results.last().unwrap().clone().unwrap();
+    let split = cli::Cli::try_parse_from(["worktime", "report", "--split-by", "project"])
+        .unwrap()
+        .command;
+    run_loop(&clock, &db, &MockStdIn::from(vec![split]), &mut recorder).await;
+    let split_out = recorder.results.last().unwrap().clone().unwrap();
+    let with_percent = ReportOptions {
+        by_project: true,
+        with_percent: true,
+        ..Default::default()
+    };
+    run_loop(&clock, &db, &report(with_percent), &mut recorder).await;
+    let percent_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(grouped_out, "-: 1.00h / acme: 6.00h / beta: 2.00h");
+    assert_eq!(split_out, grouped_out);
+    assert_eq!(
+        percent_out,
+        "-: 1.00h (11%) / acme: 6.00h (67%) / beta: 2.00h (22%)"
+    );
+    assert_eq!(acme_out, "Day's balance: 6.00h (-2.00h vs target)");
+    assert_eq!(db.get_distinct_projects().await.unwrap(), ["acme", "beta"]);
+}
+
+#[tokio::test]
+async fn should_split_project_percentages_to_100() {
+    let (clock, mut recorder, db) = setup().await;
+    for (project, start) in [(Some("acme"), 9), (Some("beta"), 10), (None, 11)] {
+        clock.set(7, start, 0);
+        db.insert_start(&clock, None, project).await.unwrap();
+        clock.set(7, start + 1, 0);
+        let id = db.get_last_session().await.unwrap().unwrap().id;
+        db.insert_stop(id, &clock).await.unwrap();
+    }
+    let with_percent = |day| {
+        clock.set(day, 18, 0);
+        vec![WorktimeCommand::Report {
+            kind: ReportKind::Day,
+            options: ReportOptions {
+                by_project: true,
+                with_percent: true,
+                ..Default::default()
+            },
+        }]
+    };
+
+    run_loop(
+        &clock,
+        &db,
+        &MockStdIn::from(with_percent(7)),
+        &mut recorder,
+    )
+    .await;
+    let thirds = recorder.results.last().unwrap().clone().unwrap();
+    run_loop(
+        &clock,
+        &db,
+        &MockStdIn::from(with_percent(8)),
+        &mut recorder,
+    )
+    .await;
+    let nothing = recorder.results.last().unwrap().clone().unwrap();
+
+    assert_eq!(
+        thirds,
+        "-: 1.00h (34%) / acme: 1.00h (33%) / beta: 1.00h (33%)"
+    );
+    let percentages: u32 = thirds
+        .split(['(', '%'])
+        .filter_map(|part| part.parse::<u32>().ok())
+        .sum();
+    assert_eq!(percentages, 100);
+    assert_eq!(nothing, "");
+}
+
+#[tokio::test]
+async fn should_print_session_table_for_report() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+    track(&clock, &db, &mut recorder, 1, (13, 16)).await;
+
+    clock.set(1, 18, 0);
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Day,
+        options: ReportOptions {
+            table: true,
+            ..Default::default()
+        },
+    }]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let table = recorder.tables.last().unwrap();
+
+    assert_eq!(
+        stdout::render_table(table, false)
+            .lines()
+            .collect::<Vec<_>>(),
+        vec![
+            "date        start    end  duration",
+            "2025-07-01  09:00  12:00     3.00h",
+            "2025-07-01  13:00  16:00     3.00h",
+            "total                        6.00h",
+        ]
+    );
+    assert_eq!(table.last().unwrap().tone, stdout::Tone::Under);
+}
+
+#[test]
+fn should_reject_report_flags_overlapping_global_ones() {
+    use clap::Parser;
+
+    let parse = |args: &[&str]| {
+        cli::Cli::try_parse_from(["worktime"].iter().chain(args)).and_then(cli::Cli::validated)
+    };
+
+    for conflicting in [
+        &["report", "--output-format", "csv", "--format", "json"][..],
+        &["--format", "plain", "report", "--output-format", "markdown"],
+        &["report", "--output", "a.log", "--output-file", "b.log"],
+        &["--output-file", "b.log", "report", "--output", "a.log"],
+    ] {
+        let err = parse(conflicting).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+    assert!(parse(&["report", "--output-format", "csv", "--output", "a.csv"]).is_ok());
+    assert!(parse(&["--format", "json", "--output-file", "b.log", "report"]).is_ok());
+}
+
+#[tokio::test]
+async fn should_shift_displayed_times_only() {
+    use clap::Parser;
+
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 12)).await;
+    let parse = |args: &[&str]| {
+        cli::Cli::try_parse_from(["worktime"].iter().chain(args))
+            .unwrap()
+            .command
+    };
+
+    clock.set(1, 18, 0);
+    let std_in = MockStdIn::from(vec![
+        parse(&["list", "--tz-shift", "-60"]),
+        parse(&["report", "--table", "--tz-shift", "-60"]),
+    ]);
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let results = &recorder.results[recorder.results.len() - 2..];
+
+    assert_eq!(results[0].clone().unwrap(), "id: 1;start: 08:00;end: 11:00");
+    assert_eq!(
+        stdout::render_table(recorder.tables.last().unwrap(), false)
+            .lines()
+            .nth(1),
+        Some("2025-07-01  08:00  11:00     3.00h")
+    );
+    let stored = db.get_last_session().await.unwrap().unwrap();
+    assert_eq!(stored.start, clock.get(1, 9, 0));
+}
+
+#[tokio::test]
+async fn should_agree_on_balance_with_holidays_across_flags() {
+    use cli::HolidayAction;
+
+    let (clock, mut recorder, db) = setup().await;
+    for day in 8..=11 {
+        track(&clock, &db, &mut recorder, day, (8, 16)).await;
+    }
+    let report = |balance_only, table| WorktimeCommand::Report {
+        kind: ReportKind::Week,
+        options: ReportOptions {
+            balance_only,
+            table,
+            ..Default::default()
+        },
+    };
+
+    clock.set(11, 18, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Add {
+                date: clock.get(7, 0, 0).date(),
+                kind: db::HolidayKind::Public,
+            },
+        },
+        report(false, false),
+        report(true, false),
+        report(false, true),
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let results = &recorder.results[recorder.results.len() - 3..];
+
+    assert!(
+        results[0]
+            .clone()
+            .unwrap()
+            .starts_with("Week's balance: 32.00h (+0.00h vs target)")
+    );
+    assert_eq!(results[1].clone().unwrap(), "+0.00h");
+    assert_eq!(
+        recorder.tables.last().unwrap().last().unwrap().tone,
+        stdout::Tone::Over
+    );
+}
+
+#[tokio::test]
+async fn should_expect_nothing_on_holidays() {
+    use cli::HolidayAction;
+
+    let (clock, mut recorder, db) = setup().await;
+    for day in 8..=11 {
+        track(&clock, &db, &mut recorder, day, (8, 16)).await;
+    }
+    let date = clock.get(7, 0, 0).date();
+
+    clock.set(11, 18, 0);
+    let std_in: MockStdIn = vec![
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Add {
+                date,
+                kind: db::HolidayKind::Vacation,
+            },
+        },
+        WorktimeCommand::Report {
+            kind: ReportKind::Week,
+            options: ReportOptions::default(),
+        },
+        WorktimeCommand::Balance { since: Some(date) },
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Remove { date },
+        },
+        WorktimeCommand::Holiday {
+            action: HolidayAction::Remove { date },
+        },
+    ]
+    .into();
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let results = &recorder.results[recorder.results.len() - 5..];
+
+    assert_eq!(results[0].clone().unwrap(), "Marked 2025-07-07 as Vacation");
+    assert_eq!(
+        results[1].clone().unwrap(),
+        "Week's balance: 32.00h (+0.00h vs target)\n1 holiday(s) included\n\
+         Mon 0.00h / Tue 8.00h / Wed 8.00h / Thu 8.00h / Fri 8.00h / Sat 0.00h / Sun 0.00h / \
+         Total 32.00h"
+    );
+    assert_eq!(
+        results[2].clone().unwrap(),
+        "Flex balance since 2025-07-07: +0.00h"
+    );
+    assert_eq!(results[3].clone().unwrap(), "Removed holiday on 2025-07-07");
+    assert!(
+        matches!(&results[4], Err(err::CommandError::Other(m)) if m == "No holiday on 2025-07-07")
+    );
+}
+
+#[tokio::test]
+async fn should_report_year() {
+    let (clock, mut recorder, db) = setup().await;
+    track(&clock, &db, &mut recorder, 1, (9, 17)).await;
+    track(&clock, &db, &mut recorder, 7, (9, 17)).await;
+    let std_in: MockStdIn = vec![WorktimeCommand::Report {
+        kind: ReportKind::Year,
+        options: ReportOptions::default(),
+    }]
+    .into();
+
+    run_loop(&clock, &db, &std_in, &mut recorder).await;
+    let last_out = recorder.results.last().unwrap().clone().unwrap();
+
+    assert!(last_out.starts_with("Year's balance: 16.00h"), "{last_out}");
+}